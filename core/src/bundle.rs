@@ -0,0 +1,372 @@
+use crate::error::{Error, Result};
+use crate::utils::openapi::unescape_token;
+use percent_encoding::percent_decode_str;
+use serde_json::{Map, Value};
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+use url::Url;
+
+/// Outcome of a bundling pass.
+#[derive(Debug)]
+pub enum Bundle {
+    /// The document with every `$ref` pointing inside it.
+    Complete(Value),
+    /// Documents that must be loaded before bundling can complete, by location.
+    Missing(Vec<String>),
+}
+
+/// Pulls whatever the `$ref`s of `root` point at in other documents into `root`.
+/// Targets are hoisted into the matching section of `components` (a schema into
+/// `components/schemas`, a response into `components/responses`, ...) and the `$ref`s
+/// rewritten to point at them. Path items have no such section and are inlined.
+///
+/// Local references that point anywhere but straight at a component (e.g.
+/// `#/components/schemas/Outer/properties/inner`) are hoisted the same way, so every
+/// `$ref` left names a component.
+///
+/// `documents` holds the documents loaded so far, keyed by the location their
+/// references resolve to: a path relative to the working directory or a URL. Pass the
+/// ones reported as [`Bundle::Missing`] on the next call.
+pub fn bundle(location: &str, root: Value, documents: &HashMap<String, Value>) -> Result<Bundle> {
+    let mut bundler = Bundler {
+        root: location.to_owned(),
+        document: root.clone(),
+        documents,
+        components: root.get("components").cloned().unwrap_or_default(),
+        hoisted: HashMap::new(),
+        names: Vec::new(),
+        inlining: Vec::new(),
+        missing: Vec::new(),
+    };
+    let mut root = bundler.walk(root, location, Kind::Other)?;
+
+    if !bundler.missing.is_empty() {
+        return Ok(Bundle::Missing(bundler.missing));
+    }
+
+    for (section, name) in bundler.names {
+        root["components"][section][&name] = bundler.components[section][&name].take();
+    }
+
+    Ok(Bundle::Complete(root))
+}
+
+/// What a value in the document is, which decides where a `$ref` in its place is
+/// hoisted to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    /// A map or list of items of a section, e.g. `components/schemas`.
+    Collection(&'static str),
+    /// An item of a section, e.g. a schema.
+    Item(&'static str),
+    Other,
+}
+
+impl Kind {
+    fn child(self, key: Option<&str>) -> Self {
+        match (self, key) {
+            (Kind::Collection(section), _) => Kind::Item(section),
+            // Properties are named by the user, so a property may be called `example`.
+            (
+                Kind::Item("schemas"),
+                Some("properties" | "patternProperties" | "dependentSchemas" | "$defs"),
+            ) => Kind::Collection("schemas"),
+            // Everything else nested in a schema is a schema, even keys named after
+            // other sections.
+            (Kind::Item("schemas"), _) => self,
+            (_, None) => Kind::Other,
+            (_, Some(key)) => match key {
+                "schema" => Kind::Item("schemas"),
+                "requestBody" => Kind::Item("requestBodies"),
+                "schemas" | "parameters" | "responses" | "requestBodies" | "headers"
+                | "examples" | "links" | "callbacks" | "securitySchemes" | "paths" => {
+                    Kind::Collection(section(key))
+                }
+                "x-webhooks" => Kind::Collection("paths"),
+                _ => Kind::Other,
+            },
+        }
+    }
+
+    /// Whether `key` holds example data, where a `$ref` is not a reference: `example`
+    /// in a schema, media type or parameter, `examples` in a schema and the `value` of
+    /// an example. Keys of name-keyed maps are never examples.
+    fn is_example(self, key: &str) -> bool {
+        match self {
+            Kind::Collection(_) => false,
+            Kind::Item("schemas") => matches!(key, "example" | "examples"),
+            Kind::Item("examples") => key == "value",
+            _ => key == "example",
+        }
+    }
+}
+
+fn section(key: &str) -> &'static str {
+    match key {
+        "schemas" => "schemas",
+        "parameters" => "parameters",
+        "responses" => "responses",
+        "requestBodies" => "requestBodies",
+        "headers" => "headers",
+        "examples" => "examples",
+        "links" => "links",
+        "callbacks" => "callbacks",
+        "securitySchemes" => "securitySchemes",
+        _ => "paths",
+    }
+}
+
+struct Bundler<'a> {
+    root: String,
+    /// The root as it was before bundling, where its local references point.
+    document: Value,
+    documents: &'a HashMap<String, Value>,
+    /// `components` of the root, growing as targets are hoisted.
+    components: Value,
+    /// Local `$ref` each hoisted target was given, by location and pointer.
+    hoisted: HashMap<(String, String), String>,
+    /// Section and name of each hoisted target, in `components`.
+    names: Vec<(&'static str, String)>,
+    /// Targets being inlined, to catch references that inline themselves.
+    inlining: Vec<(String, String)>,
+    missing: Vec<String>,
+}
+
+impl Bundler<'_> {
+    /// Walks a value found in the document at `base`, where `#/...` references point.
+    fn walk(&mut self, value: Value, base: &str, kind: Kind) -> Result<Value> {
+        match value {
+            Value::Object(mut map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    if let Some(target) = self.reference(reference, base, kind)? {
+                        return Ok(match target {
+                            Target::Ref(local) => {
+                                map.insert("$ref".to_owned(), Value::String(local));
+                                Value::Object(map)
+                            }
+                            Target::Inline(value) => value,
+                        });
+                    }
+
+                    return Ok(Value::Object(map));
+                }
+
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let value = match kind.is_example(&key) {
+                            true => value,
+                            false => self.walk(value, base, kind.child(Some(&key)))?,
+                        };
+
+                        Ok((key, value))
+                    })
+                    .collect::<Result<Map<String, Value>>>()
+                    .map(Value::Object)
+            }
+            Value::Array(values) => values
+                .into_iter()
+                .map(|value| self.walk(value, base, kind.child(None)))
+                .collect::<Result<Vec<Value>>>()
+                .map(Value::Array),
+            value => Ok(value),
+        }
+    }
+
+    /// Works out what replaces `reference`, or `None` to keep it until the document it
+    /// points into is loaded.
+    fn reference(&mut self, reference: &str, base: &str, kind: Kind) -> Result<Option<Target>> {
+        let (document, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+        let location = match document.is_empty() {
+            true => base.to_owned(),
+            false => resolve_location(base, document),
+        };
+
+        if location == self.root && is_component(pointer) {
+            return Ok(Some(Target::Ref(format!("#{pointer}"))));
+        }
+
+        let key = (location.to_owned(), pointer.to_owned());
+
+        if let Some(local) = self.hoisted.get(&key) {
+            return Ok(Some(Target::Ref(local.to_owned())));
+        }
+
+        let document = match location == self.root {
+            true => Some(&self.document),
+            false => self.documents.get(&location),
+        };
+        let Some(document) = document else {
+            if !self.missing.contains(&location) {
+                self.missing.push(location);
+            }
+
+            return Ok(None);
+        };
+        let decoded = percent_decode_str(pointer).decode_utf8_lossy();
+        let target = document
+            .pointer(&decoded)
+            .ok_or_else(|| Error::reference(reference, format!("not found in {location}")))?
+            .to_owned();
+
+        match kind {
+            Kind::Item(section) if section != "paths" => {
+                let name = self.reserve_name(section, &location, &decoded);
+                let local = format!("#/components/{section}/{name}");
+                self.hoisted.insert(key, local.to_owned());
+
+                let target = self.walk(target, &location, kind)?;
+                self.components[section][&name] = target;
+
+                Ok(Some(Target::Ref(local)))
+            }
+            _ => {
+                if self.inlining.contains(&key) {
+                    return Err(Error::reference(reference, "circular reference"));
+                }
+
+                self.inlining.push(key);
+                let target = self.walk(target, &location, kind);
+                self.inlining.pop();
+
+                Ok(Some(Target::Inline(target?)))
+            }
+        }
+    }
+
+    /// Names a hoisted target after the last segment of its pointer, or the file it
+    /// lives in, numbered if the section already has a component of that name.
+    fn reserve_name(&mut self, section: &'static str, location: &str, pointer: &str) -> String {
+        let name = match pointer.rsplit('/').next().filter(|name| !name.is_empty()) {
+            Some(token) => unescape_token(token),
+            None => location
+                .rsplit('/')
+                .next()
+                .and_then(|file| file.split('.').next())
+                .unwrap_or_default()
+                .to_owned(),
+        };
+        let name = name
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() || "._-".contains(c) {
+                true => c,
+                false => '_',
+            })
+            .collect::<String>();
+        let taken = |name: &str| !self.components[section][name].is_null();
+        let name = match taken(&name) {
+            false => name,
+            true => (2..)
+                .map(|n| format!("{name}_{n}"))
+                .find(|name| !taken(name))
+                .unwrap_or_default(),
+        };
+
+        // Claimed up front, so targets referring back to it do not take the name.
+        self.components[section][&name] = Value::Bool(true);
+        self.names.push((section, name.to_owned()));
+
+        name
+    }
+}
+
+/// Whether a local pointer names a component, as in `/components/schemas/Pet`.
+fn is_component(pointer: &str) -> bool {
+    let tokens = pointer.split('/').collect::<Vec<&str>>();
+
+    matches!(tokens.as_slice(), ["", "components", _, _])
+}
+
+enum Target {
+    Ref(String),
+    Inline(Value),
+}
+
+/// Resolves a reference to another document against the location of the document it
+/// appears in.
+fn resolve_location(base: &str, reference: &str) -> String {
+    if let Ok(url) = Url::parse(reference) {
+        return url.to_string();
+    }
+
+    if let Ok(base) = Url::parse(base) {
+        return base
+            .join(reference)
+            .map(String::from)
+            .unwrap_or(reference.to_owned());
+    }
+
+    let path = Path::new(base)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(reference);
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn resolve_relative_locations() {
+        assert_eq!(
+            resolve_location("data/multi/openapi.yaml", "schemas/user.yaml"),
+            "data/multi/schemas/user.yaml"
+        );
+        assert_eq!(
+            resolve_location("data/multi/paths/users.yaml", "../common.json"),
+            "data/multi/common.json"
+        );
+        assert_eq!(
+            resolve_location("http://localhost/specs/openapi.json", "./common.json"),
+            "http://localhost/specs/common.json"
+        );
+        assert_eq!(
+            resolve_location("data/openapi.json", "http://localhost/common.json"),
+            "http://localhost/common.json"
+        );
+    }
+
+    #[test]
+    fn keep_examples_and_properties_named_example() {
+        let example = json!({
+            "type": "object",
+            "required": ["example"],
+            "properties": {
+                "example": { "type": "integer" }
+            },
+            "example": { "example": { "$ref": "not/a/reference.json" } }
+        });
+        let root = json!({
+            "openapi": "3.0.3",
+            "paths": {},
+            "components": { "schemas": { "example": example } }
+        });
+
+        let Bundle::Complete(bundled) = bundle("openapi.json", root, &HashMap::new()).unwrap()
+        else {
+            panic!("nothing to load");
+        };
+
+        assert_eq!(bundled["components"]["schemas"]["example"], example);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::config::{Config, Service};
+use crate::discover::{discover, SpecUrl};
+use crate::error::{Result, Warning};
+use crate::humbler::{render_markdown_table, ApiInfo, Humbler};
+use std::panic;
+
+/// The operations of several services, each tagged with the service it belongs to.
+#[derive(Debug, Clone)]
+pub struct Catalogue {
+    config: Config,
+    pub api_infos: Vec<ApiInfo>,
+    /// Operations, or whole services, skipped in lenient mode.
+    pub warnings: Vec<Warning>,
+}
+
+impl Catalogue {
+    /// A catalogue of the `services` of `config`.
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            api_infos: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    pub async fn run(self) -> Result<Self> {
+        self.collect(None).await
+    }
+
+    pub async fn search(&self, keyword: String) -> Result<Self> {
+        self.clone().collect(Some(keyword)).await
+    }
+
+    pub fn render_markdown_table(&self) -> String {
+        render_markdown_table(&self.api_infos, self.config.responses)
+    }
+
+    /// Renders every service at once, keeping them in the order they are configured. In
+    /// lenient mode a service that cannot be loaded becomes a warning.
+    async fn collect(self, keyword: Option<String>) -> Result<Self> {
+        let tasks = self
+            .config
+            .services
+            .iter()
+            .map(|service| {
+                let task = service_api_infos(service.clone(), self.config.clone(), keyword.clone());
+
+                (service.name.to_owned(), tokio::spawn(task))
+            })
+            .collect::<Vec<_>>();
+        let mut api_infos = Vec::new();
+        let mut warnings = Vec::new();
+
+        for (service, task) in tasks {
+            match task
+                .await
+                .unwrap_or_else(|e| panic::resume_unwind(e.into_panic()))
+            {
+                Ok((service_api_infos, service_warnings)) => {
+                    api_infos.extend(service_api_infos);
+                    warnings.extend(service_warnings);
+                }
+                Err(e) if self.config.lenient => warnings.push(Warning {
+                    service: Some(service),
+                    pointer: String::new(),
+                    message: e.to_string(),
+                }),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Self {
+            api_infos,
+            warnings,
+            ..self
+        })
+    }
+}
+
+async fn service_api_infos(
+    service: Service,
+    config: Config,
+    keyword: Option<String>,
+) -> Result<(Vec<ApiInfo>, Vec<Warning>)> {
+    let specs = match &service.openapi_json_url {
+        Some(url) => vec![SpecUrl {
+            name: None,
+            url: url.to_owned(),
+        }],
+        None => discover(&config.source.clone().with_env()?, &service.swagger_ui_url).await?,
+    };
+    let mut api_infos = Vec::new();
+    let mut warnings = Vec::new();
+
+    for spec in specs {
+        let name = match &spec.name {
+            Some(group) => format!("{}/{group}", service.name),
+            None => service.name.to_owned(),
+        };
+        let humbler = Humbler::new(spec.ui_url(&service.swagger_ui_url), spec.url.to_owned())
+            .with_config(config.clone())
+            .with_service(name);
+        let humbler = match &keyword {
+            Some(keyword) => humbler.search(keyword.to_owned()).await?,
+            None => humbler.run().await?,
+        };
+
+        api_infos.extend(humbler.api_infos);
+        warnings.extend(humbler.warnings);
+    }
+
+    Ok((api_infos, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn service(name: &str, openapi_json_url: Option<&str>, swagger_ui_url: &str) -> Service {
+        Service {
+            name: name.to_owned(),
+            swagger_ui_url: swagger_ui_url.to_owned(),
+            openapi_json_url: openapi_json_url.map(str::to_owned),
+        }
+    }
+
+    #[tokio::test]
+    async fn merge_services() {
+        let config = Config {
+            lenient: true,
+            services: vec![
+                service("gateway", Some("data/gateway.json"), "http://gateway/docs"),
+                service("pets", Some("data/pets-31.json"), "http://pets/docs"),
+                service("gone", Some("data/gone.json"), "http://gone/docs"),
+            ],
+            ..Config::default()
+        };
+        let catalogue = Catalogue::new(config).run().await.unwrap();
+        let services = catalogue
+            .api_infos
+            .iter()
+            .map(|api_info| api_info.service.as_deref().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(services.first(), Some(&"gateway"));
+        assert_eq!(services.last(), Some(&"pets"));
+        assert!(catalogue.api_infos[0]
+            .swagger_url
+            .starts_with("http://gateway/docs#"));
+        assert_eq!(catalogue.warnings.len(), 1);
+        assert!(catalogue.warnings[0]
+            .to_string()
+            .starts_with("gone: could not fetch data/gone.json: "));
+        assert!(catalogue
+            .render_markdown_table()
+            .starts_with("| Service | Path | Method |"));
+
+        let orders = catalogue.search("orders".to_owned()).await.unwrap();
+
+        assert!(!orders.api_infos.is_empty());
+        assert!(orders
+            .api_infos
+            .iter()
+            .all(|api_info| api_info.service.as_deref() == Some("gateway")));
+    }
+
+    #[tokio::test]
+    async fn merge_springdoc_groups() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v3/api-docs/swagger-config"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"urls": [{"url": "/v3/api-docs/pets", "name": "pets"}]}"#),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v3/api-docs/pets"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(include_str!("../data/pets-31.json")),
+            )
+            .mount(&server)
+            .await;
+
+        let swagger_ui_url = format!("{}/swagger-ui/index.html", server.uri());
+        let config = Config {
+            services: vec![service("shop", None, &swagger_ui_url)],
+            ..Config::default()
+        };
+        let catalogue = Catalogue::new(config).run().await.unwrap();
+
+        assert!(!catalogue.api_infos.is_empty());
+        assert!(catalogue.api_infos.iter().all(|api_info| {
+            api_info.service.as_deref() == Some("shop/pets")
+                && api_info
+                    .swagger_url
+                    .starts_with(&format!("{swagger_ui_url}?urls.primaryName=pets#"))
+        }));
+    }
+}
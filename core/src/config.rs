@@ -1,18 +1,229 @@
-use std::{fs::File, io::Read, path::Path};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 
-use anyhow::{Error, Result};
+use crate::error::{Error, Result};
+use indexmap::IndexMap;
 use serde::Deserialize;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default, Clone)]
 pub struct Config {
+    #[serde(default)]
     pub filter_keywords: Vec<String>,
+    /// Render enumerated schemas as their bare type (e.g. `"string"`) instead of
+    /// listing their members (e.g. `"available|pending|sold"`).
+    #[serde(default)]
+    pub compact_enums: bool,
+    /// Annotate each leaf type with its validation keywords (`minLength`, `maximum`,
+    /// `pattern`, `minItems`, ...).
+    #[serde(default)]
+    pub constraints: bool,
+    /// Which responses the markdown table shows: `"success"` (2xx only) or `"all"`.
+    #[serde(default)]
+    pub responses: ResponseMode,
+    /// Leave headers every client sends anyway (`Accept`, `Authorization`, ...) out of
+    /// the parameters.
+    #[serde(default)]
+    pub hide_well_known_headers: bool,
+    /// Report operations that cannot be rendered as warnings and keep going, instead of
+    /// failing the whole run.
+    #[serde(default)]
+    pub lenient: bool,
+    /// Which documentation viewer the links in the table point into.
+    #[serde(default)]
+    pub link_style: LinkStyle,
+    /// How specs are fetched over HTTP, under `[source]`.
+    #[serde(default)]
+    pub source: Source,
+    /// Services merged into one catalogue, as `[[services]]` entries.
+    #[serde(default)]
+    pub services: Vec<Service>,
 }
 
-pub fn load_config<P: AsRef<Path>>(path: P) -> Result<Config, Error> {
-    let mut file = File::open(path)?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-    let config: Config = toml::from_str(&contents)?;
+const WELL_KNOWN_HEADERS: [&str; 12] = [
+    "Accept",
+    "Accept-Encoding",
+    "Accept-Language",
+    "Authorization",
+    "Cache-Control",
+    "Connection",
+    "Content-Length",
+    "Content-Type",
+    "Host",
+    "Origin",
+    "Referer",
+    "User-Agent",
+];
+
+impl Config {
+    pub fn is_hidden_header(&self, name: &str) -> bool {
+        self.hide_well_known_headers
+            && WELL_KNOWN_HEADERS
+                .iter()
+                .any(|header| header.eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseMode {
+    #[default]
+    Success,
+    All,
+}
+
+/// Documentation viewers Humbler can deep link into, set as `"swagger-ui"`, `"redoc"`,
+/// `"scalar"` or `"rapidoc"`.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkStyle {
+    #[default]
+    #[serde(rename = "swagger-ui")]
+    SwaggerUi,
+    Redoc,
+    Scalar,
+    RapiDoc,
+}
+
+/// A service of the catalogue. Without `openapi_json_url`, every spec its Swagger UI
+/// lists is merged, named `{name}/{group}` when springdoc groups them.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Service {
+    pub name: String,
+    pub swagger_ui_url: String,
+    #[serde(default)]
+    pub openapi_json_url: Option<String>,
+}
+
+/// Credentials and connection settings for fetching specs, e.g. from behind a gateway.
+/// Each setting can also be given through the environment, see [`Source::with_env`].
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Source {
+    /// Sent as `Authorization: Bearer <token>`.
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+    /// Sent as `Authorization: Basic ...`, unless there is a bearer token.
+    #[serde(default)]
+    pub basic_auth: Option<BasicAuth>,
+    /// Sent with every request, after the `Authorization` header so they can replace it.
+    #[serde(default)]
+    pub headers: IndexMap<String, String>,
+    /// Seconds to wait for a response.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// PEM certificate to trust on top of the system ones, for a private CA.
+    #[serde(default)]
+    pub ca_cert: Option<PathBuf>,
+    /// Skip TLS certificate validation altogether, e.g. for a self-signed gateway.
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct BasicAuth {
+    pub username: String,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl Source {
+    /// Overrides settings with the ones set in the environment: `HUMBLER_BEARER_TOKEN`,
+    /// `HUMBLER_BASIC_USERNAME`, `HUMBLER_BASIC_PASSWORD`, `HUMBLER_TIMEOUT_SECS`,
+    /// `HUMBLER_CA_CERT`, `HUMBLER_ACCEPT_INVALID_CERTS` and `HUMBLER_HEADERS`, which
+    /// holds `Name: value` pairs separated by `;`.
+    pub fn with_env(self) -> Result<Self> {
+        self.with_vars(|name| env::var(name).ok())
+    }
+
+    fn with_vars(mut self, var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        if let Some(token) = var("HUMBLER_BEARER_TOKEN") {
+            self.bearer_token = Some(token);
+        }
+
+        if let Some(username) = var("HUMBLER_BASIC_USERNAME") {
+            self.basic_auth = Some(BasicAuth {
+                username,
+                password: var("HUMBLER_BASIC_PASSWORD"),
+            });
+        }
+
+        if let Some(timeout) = var("HUMBLER_TIMEOUT_SECS") {
+            let timeout = timeout
+                .parse()
+                .map_err(|e| Error::parse("HUMBLER_TIMEOUT_SECS", e))?;
+            self.timeout_secs = Some(timeout);
+        }
+
+        if let Some(path) = var("HUMBLER_CA_CERT") {
+            self.ca_cert = Some(PathBuf::from(path));
+        }
+
+        if let Some(accept) = var("HUMBLER_ACCEPT_INVALID_CERTS") {
+            self.accept_invalid_certs = matches!(accept.as_str(), "1" | "true");
+        }
+
+        for header in var("HUMBLER_HEADERS")
+            .iter()
+            .flat_map(|headers| headers.split(';'))
+        {
+            let Some((name, value)) = header.split_once(':') else {
+                continue;
+            };
+
+            self.headers
+                .insert(name.trim().to_owned(), value.trim().to_owned());
+        }
+
+        Ok(self)
+    }
+}
+
+pub fn load_config<P: AsRef<Path>>(path: P) -> Result<Config> {
+    let location = path.as_ref().display().to_string();
+    let contents = fs::read_to_string(path).map_err(|e| Error::fetch(&location, e))?;
+    let config: Config = toml::from_str(&contents).map_err(|e| Error::parse(&location, e))?;
 
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn override_source_with_env() {
+        let source: Source = toml::from_str(
+            r#"
+            bearer_token = "from-file"
+            timeout_secs = 5
+
+            [headers]
+            X-Api-Key = "from-file"
+            X-Tenant = "acme"
+            "#,
+        )
+        .unwrap();
+        let vars = HashMap::from([
+            ("HUMBLER_BEARER_TOKEN", "from-env"),
+            ("HUMBLER_ACCEPT_INVALID_CERTS", "true"),
+            ("HUMBLER_HEADERS", "X-Api-Key: from-env; X-Trace: 1"),
+        ]);
+        let source = source
+            .with_vars(|name| vars.get(name).map(|value| value.to_string()))
+            .unwrap();
+
+        assert_eq!(source.bearer_token.as_deref(), Some("from-env"));
+        assert_eq!(source.timeout_secs, Some(5));
+        assert!(source.accept_invalid_certs);
+        assert_eq!(
+            source.headers,
+            IndexMap::from([
+                ("X-Api-Key".to_owned(), "from-env".to_owned()),
+                ("X-Tenant".to_owned(), "acme".to_owned()),
+                ("X-Trace".to_owned(), "1".to_owned()),
+            ])
+        );
+    }
+}
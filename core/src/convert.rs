@@ -0,0 +1,16 @@
+pub mod openapi31;
+pub mod swagger2;
+
+use serde_json::Value;
+
+/// Brings a Swagger 2.0 or OpenAPI 3.1 spec to the OpenAPI 3.0 shape `openapiv3`
+/// reads. OpenAPI 3.0 documents are returned as is.
+pub fn normalize(document: Value) -> Value {
+    let version = |field: &str| document.get(field).and_then(Value::as_str);
+
+    match (version("swagger"), version("openapi")) {
+        (Some(version), _) if version.starts_with("2.") => swagger2::upgrade(document),
+        (_, Some(version)) if version.starts_with("3.1") => openapi31::downgrade(document),
+        _ => document,
+    }
+}
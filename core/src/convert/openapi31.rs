@@ -0,0 +1,397 @@
+use crate::utils::openapi::pointer_token;
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Keywords describing a schema as a whole, which stay put when a schema with several
+/// types is split into one `oneOf` alternative per type.
+const ANNOTATIONS: [&str; 9] = [
+    "title",
+    "description",
+    "default",
+    "example",
+    "readOnly",
+    "writeOnly",
+    "deprecated",
+    "externalDocs",
+    "nullable",
+];
+
+/// Down-converts an OpenAPI 3.1 document to OpenAPI 3.0:
+/// - `type` arrays become `nullable` and, with several types, a `oneOf` per type.
+///   `{"type": "null"}` alternatives of `oneOf`/`anyOf` become `nullable` too.
+/// - `const` becomes a single-member `enum`, and `examples` the first `example`.
+/// - Numeric `exclusiveMinimum`/`exclusiveMaximum` become bounds with a boolean flag.
+/// - `$defs` are hoisted into `components/schemas`, and `$ref`s into them follow. A def
+///   named like a component, or like another def, is prefixed with the component
+///   holding it (e.g. `Wrapper_Item`).
+/// - `prefixItems` without `items` become `items` accepting any of them.
+/// - `webhooks` move to `x-webhooks`, where they are rendered alongside the paths.
+/// - `paths`, optional in 3.1, is added empty to webhooks-only documents.
+pub fn downgrade(document: Value) -> Value {
+    let Value::Object(mut openapi) = document else {
+        return document;
+    };
+    openapi.insert("openapi".to_owned(), json!("3.0.3"));
+
+    if let Some(webhooks) = openapi.remove("webhooks") {
+        openapi.insert("x-webhooks".to_owned(), webhooks);
+    }
+
+    openapi.entry("paths").or_insert(json!({}));
+
+    let mut openapi = Value::Object(openapi);
+    rename_defs(&mut openapi);
+
+    let mut defs = Map::new();
+    let mut openapi = downgrade_document(openapi, &mut defs);
+
+    match openapi.pointer_mut("/components/schemas") {
+        Some(Value::Object(schemas)) => {
+            for (name, schema) in defs {
+                schemas.entry(name).or_insert(schema);
+            }
+        }
+        _ if !defs.is_empty() => openapi["components"]["schemas"] = Value::Object(defs),
+        _ => {}
+    }
+
+    openapi
+}
+
+/// Gives every `$defs` entry a name that is free in `components/schemas`, so hoisting it
+/// neither loses it nor makes `$ref`s into it land on another schema.
+fn rename_defs(openapi: &mut Value) {
+    let mut taken = openapi
+        .pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .map(|schemas| schemas.keys().cloned().collect::<HashSet<String>>())
+        .unwrap_or_default();
+    let mut names = HashMap::new();
+
+    collect_defs(openapi, "#", &mut taken, &mut names);
+
+    *openapi = rename(openapi.take(), "#", &names, &mut Vec::new());
+}
+
+/// Picks a name for each `$defs` entry, keyed by its pointer, e.g.
+/// `#/components/schemas/Wrapper/$defs/Item` → `Wrapper_Item` when `Item` is taken.
+fn collect_defs(
+    value: &Value,
+    pointer: &str,
+    taken: &mut HashSet<String>,
+    names: &mut HashMap<String, String>,
+) {
+    let owner = pointer
+        .strip_prefix("#/components/schemas/")
+        .and_then(|rest| rest.split('/').next());
+
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Object(defs)) = map.get("$defs") {
+                for name in defs.keys() {
+                    let prefixed = owner.map(|owner| format!("{owner}_{name}"));
+                    let unique = [Some(name.to_owned()), prefixed]
+                        .into_iter()
+                        .flatten()
+                        .chain((2..).map(|n| format!("{name}_{n}")))
+                        .find(|candidate| !taken.contains(candidate))
+                        .unwrap_or_default();
+
+                    taken.insert(unique.to_owned());
+                    names.insert(format!("{pointer}/$defs/{}", pointer_token(name)), unique);
+                }
+            }
+
+            for (key, value) in map {
+                collect_defs(
+                    value,
+                    &format!("{pointer}/{}", pointer_token(key)),
+                    taken,
+                    names,
+                );
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                collect_defs(value, &format!("{pointer}/{index}"), taken, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Renames `$defs` entries to the names picked for them and points `$ref`s at those
+/// names. A `$ref` such as `#/$defs/Item` refers to the nearest `$defs` holding `Item`;
+/// `scopes` are the pointers of the schemas with `$defs` around `value`.
+fn rename(
+    value: Value,
+    pointer: &str,
+    names: &HashMap<String, String>,
+    scopes: &mut Vec<String>,
+) -> Value {
+    match value {
+        Value::Object(map) => {
+            let scoped = map.contains_key("$defs");
+
+            if scoped {
+                scopes.push(pointer.to_owned());
+            }
+
+            let map = map
+                .into_iter()
+                .map(|(key, value)| {
+                    let child = format!("{pointer}/{}", pointer_token(&key));
+                    let value = match (key.as_str(), value) {
+                        ("$ref", Value::String(reference)) => {
+                            json!(rename_reference(reference, names, scopes))
+                        }
+                        ("$defs", Value::Object(defs)) => Value::Object(
+                            defs.into_iter()
+                                .map(|(name, def)| {
+                                    let def_pointer = format!("{child}/{}", pointer_token(&name));
+                                    let def = rename(def, &def_pointer, names, scopes);
+
+                                    (names.get(&def_pointer).cloned().unwrap_or(name), def)
+                                })
+                                .collect(),
+                        ),
+                        (_, value) => rename(value, &child, names, scopes),
+                    };
+
+                    (key, value)
+                })
+                .collect::<Map<String, Value>>();
+
+            if scoped {
+                scopes.pop();
+            }
+
+            Value::Object(map)
+        }
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(index, value)| rename(value, &format!("{pointer}/{index}"), names, scopes))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+fn rename_reference(
+    reference: String,
+    names: &HashMap<String, String>,
+    scopes: &[String],
+) -> String {
+    let Some((_, name)) = reference.rsplit_once("/$defs/") else {
+        return reference;
+    };
+    let name = names.get(&reference).or_else(|| {
+        scopes
+            .iter()
+            .rev()
+            .find_map(|scope| names.get(&format!("{scope}/$defs/{name}")))
+    });
+
+    match name {
+        Some(name) => format!("#/components/schemas/{}", pointer_token(name)),
+        None => reference,
+    }
+}
+
+/// Walks the parts of the document around schemas, downgrading every schema found under
+/// a `schema` key or in `components/schemas`. Examples are user data and left alone.
+fn downgrade_document(value: Value, defs: &mut Map<String, Value>) -> Value {
+    match value {
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match (key.as_str(), value) {
+                    ("schema", schema) => downgrade_schema(schema, defs),
+                    ("schemas", Value::Object(schemas)) => {
+                        Value::Object(downgrade_map(schemas, defs))
+                    }
+                    ("example" | "examples", value) => value,
+                    (_, value) => downgrade_document(value, defs),
+                };
+
+                (key, value)
+            })
+            .collect::<Map<String, Value>>()
+            .into(),
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|value| downgrade_document(value, defs))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// Boolean schemas become `{}` (anything) and `{"not": {}}` (nothing).
+fn downgrade_schema(schema: Value, defs: &mut Map<String, Value>) -> Value {
+    let mut schema = match schema {
+        Value::Object(schema) => schema,
+        Value::Bool(true) => return json!({}),
+        Value::Bool(false) => return json!({ "not": {} }),
+        schema => return schema,
+    };
+
+    if let Some(Value::String(reference)) = schema.get_mut("$ref") {
+        if let Some((_, name)) = reference.rsplit_once("/$defs/") {
+            *reference = format!("#/components/schemas/{name}");
+        }
+    }
+
+    if let Some(Value::Object(nested)) = schema.remove("$defs") {
+        for (name, nested) in downgrade_map(nested, defs) {
+            defs.entry(name).or_insert(nested);
+        }
+    }
+
+    if let Some(value) = schema.remove("const") {
+        schema.insert("enum".to_owned(), json!([value]));
+    }
+
+    if let Some(Value::Array(examples)) = schema.remove("examples") {
+        if let Some(example) = examples.into_iter().next() {
+            schema.entry("example").or_insert(example);
+        }
+    }
+
+    for (bound, exclusive) in [
+        ("minimum", "exclusiveMinimum"),
+        ("maximum", "exclusiveMaximum"),
+    ] {
+        if let Some(value @ Value::Number(_)) = schema.get(exclusive).cloned() {
+            schema.insert(bound.to_owned(), value);
+            schema.insert(exclusive.to_owned(), json!(true));
+        }
+    }
+
+    if let Some(prefix_items) = schema.remove("prefixItems") {
+        schema
+            .entry("items")
+            .or_insert(json!({ "anyOf": prefix_items }));
+    }
+
+    drop_null_alternatives(&mut schema);
+
+    let mut schema = schema
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match (key.as_str(), value) {
+                ("additionalProperties", value @ Value::Bool(_)) => value,
+                (
+                    "items"
+                    | "additionalProperties"
+                    | "not"
+                    | "contains"
+                    | "propertyNames"
+                    | "if"
+                    | "then"
+                    | "else"
+                    | "unevaluatedItems"
+                    | "unevaluatedProperties",
+                    value,
+                ) => downgrade_schema(value, defs),
+                ("properties" | "patternProperties" | "dependentSchemas", Value::Object(map)) => {
+                    Value::Object(downgrade_map(map, defs))
+                }
+                ("allOf" | "oneOf" | "anyOf", Value::Array(schemas)) => Value::Array(
+                    schemas
+                        .into_iter()
+                        .map(|schema| downgrade_schema(schema, defs))
+                        .collect(),
+                ),
+                (_, value) => value,
+            };
+
+            (key, value)
+        })
+        .collect::<Map<String, Value>>();
+
+    downgrade_type(&mut schema);
+
+    Value::Object(schema)
+}
+
+fn downgrade_map(map: Map<String, Value>, defs: &mut Map<String, Value>) -> Map<String, Value> {
+    map.into_iter()
+        .map(|(name, schema)| (name, downgrade_schema(schema, defs)))
+        .collect()
+}
+
+/// `oneOf: [X, {"type": "null"}]` is how 3.1 spells a nullable reference. The `null`
+/// alternative becomes `nullable`, and a single remaining alternative an `allOf`.
+fn drop_null_alternatives(schema: &mut Map<String, Value>) {
+    for keyword in ["oneOf", "anyOf"] {
+        let Some(Value::Array(alternatives)) = schema.remove(keyword) else {
+            continue;
+        };
+        let (nulls, alternatives) = alternatives
+            .into_iter()
+            .partition::<Vec<Value>, _>(|alternative| *alternative == json!({ "type": "null" }));
+
+        if !nulls.is_empty() {
+            schema.insert("nullable".to_owned(), json!(true));
+        }
+
+        let keyword = match (nulls.is_empty(), alternatives.len()) {
+            (false, 1) if !schema.contains_key("allOf") => "allOf",
+            _ => keyword,
+        };
+
+        schema.insert(keyword.to_owned(), Value::Array(alternatives));
+    }
+}
+
+fn downgrade_type(schema: &mut Map<String, Value>) {
+    let types = match schema.remove("type") {
+        Some(Value::Array(types)) => types,
+        Some(Value::String(typ)) => vec![json!(typ)],
+        Some(typ) => {
+            schema.insert("type".to_owned(), typ);
+            return;
+        }
+        None => return,
+    };
+    let (nulls, mut types) = types
+        .into_iter()
+        .partition::<Vec<Value>, _>(|typ| *typ == "null");
+
+    if !nulls.is_empty() {
+        schema.insert("nullable".to_owned(), json!(true));
+    }
+
+    match types.len() {
+        0 => {}
+        1 => {
+            schema.insert("type".to_owned(), types.remove(0));
+        }
+        _ => {
+            let keywords = schema
+                .keys()
+                .filter(|key| !ANNOTATIONS.contains(&key.as_str()))
+                .cloned()
+                .collect::<Vec<String>>();
+            let keywords = keywords
+                .into_iter()
+                .filter_map(|key| Some((key.to_owned(), schema.remove(&key)?)))
+                .collect::<Map<String, Value>>();
+            let alternatives = types
+                .into_iter()
+                .map(|typ| {
+                    let mut alternative = keywords.clone();
+                    alternative.insert("type".to_owned(), typ);
+
+                    Value::Object(alternative)
+                })
+                .collect();
+
+            schema.insert("oneOf".to_owned(), Value::Array(alternatives));
+        }
+    }
+}
@@ -0,0 +1,499 @@
+use serde_json::{json, Map, Value};
+
+const DEFAULT_MEDIA_TYPE: &str = "application/json";
+
+const METHODS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+
+/// Keywords Swagger 2.0 puts on non-body parameters and headers themselves, which move
+/// into their `schema` in OpenAPI 3.0.
+const SCHEMA_KEYWORDS: [&str; 16] = [
+    "type",
+    "format",
+    "items",
+    "enum",
+    "default",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "multipleOf",
+];
+
+/// Upgrades a Swagger 2.0 document to OpenAPI 3.0:
+/// - `definitions`, `parameters` and `responses` move under `components`, and `$ref`s
+///   follow them.
+/// - `body` and `formData` parameters become a `requestBody`, one entry per media type
+///   the operation `consumes`.
+/// - Response schemas get one entry per media type the operation `produces`.
+/// - `host`, `basePath` and `schemes` become `servers`.
+///
+/// Security definitions are not carried over, as Humbler does not render them.
+pub fn upgrade(document: Value) -> Value {
+    let Value::Object(mut swagger) = document else {
+        return document;
+    };
+    let upgrade = Upgrade {
+        consumes: media_types(swagger.remove("consumes"))
+            .unwrap_or(vec![DEFAULT_MEDIA_TYPE.to_owned()]),
+        produces: media_types(swagger.remove("produces"))
+            .unwrap_or(vec![DEFAULT_MEDIA_TYPE.to_owned()]),
+        parameters: object(swagger.remove("parameters")),
+    };
+
+    let mut openapi = Map::new();
+    openapi.insert("openapi".to_owned(), json!("3.0.3"));
+
+    let servers = servers(&mut swagger);
+
+    if !servers.is_empty() {
+        openapi.insert("servers".to_owned(), Value::Array(servers));
+    }
+
+    for (key, value) in swagger.iter() {
+        if ["info", "tags", "externalDocs", "security"].contains(&key.as_str())
+            || key.starts_with("x-")
+        {
+            openapi.insert(key.to_owned(), value.to_owned());
+        }
+    }
+
+    let paths = object(swagger.remove("paths"))
+        .into_iter()
+        .map(|(path, path_item)| (path, upgrade.path_item(path_item)))
+        .collect::<Map<String, Value>>();
+    openapi.insert("paths".to_owned(), Value::Object(paths));
+
+    let schemas = object(swagger.remove("definitions"))
+        .into_iter()
+        .map(|(name, schema)| (name, upgrade_schema(schema)))
+        .collect::<Map<String, Value>>();
+    let parameters = upgrade
+        .parameters
+        .iter()
+        .filter(|(_, parameter)| !is_body(parameter))
+        .map(|(name, parameter)| (name.to_owned(), upgrade_parameter(parameter.to_owned())))
+        .collect::<Map<String, Value>>();
+    let responses = object(swagger.remove("responses"))
+        .into_iter()
+        .map(|(name, response)| (name, upgrade_response(response, &upgrade.produces)))
+        .collect::<Map<String, Value>>();
+    openapi.insert(
+        "components".to_owned(),
+        json!({
+            "schemas": schemas,
+            "parameters": parameters,
+            "responses": responses,
+        }),
+    );
+
+    rewrite_refs(Value::Object(openapi))
+}
+
+/// Document-wide defaults an operation falls back on.
+struct Upgrade {
+    consumes: Vec<String>,
+    produces: Vec<String>,
+    parameters: Map<String, Value>,
+}
+
+impl Upgrade {
+    /// Body and form parameters shared at the path level are handed down to every
+    /// operation, since they end up in each operation's `requestBody`.
+    fn path_item(&self, path_item: Value) -> Value {
+        let Value::Object(mut path_item) = path_item else {
+            return path_item;
+        };
+        let (shared, parameters) = array(path_item.remove("parameters"))
+            .into_iter()
+            .partition::<Vec<Value>, _>(|parameter| is_body(&self.resolve(parameter)));
+
+        if !parameters.is_empty() {
+            let parameters = parameters.into_iter().map(upgrade_parameter).collect();
+            path_item.insert("parameters".to_owned(), Value::Array(parameters));
+        }
+
+        path_item
+            .into_iter()
+            .map(|(key, value)| match METHODS.contains(&key.as_str()) {
+                true => (key, self.operation(value, &shared)),
+                false => (key, value),
+            })
+            .collect::<Map<String, Value>>()
+            .into()
+    }
+
+    fn operation(&self, operation: Value, shared: &[Value]) -> Value {
+        let Value::Object(mut operation) = operation else {
+            return operation;
+        };
+        let consumes =
+            media_types(operation.remove("consumes")).unwrap_or(self.consumes.to_owned());
+        let produces =
+            media_types(operation.remove("produces")).unwrap_or(self.produces.to_owned());
+        operation.remove("schemes");
+
+        let mut parameters = Vec::new();
+        let mut body = None;
+        let mut form = Vec::new();
+
+        for parameter in shared
+            .iter()
+            .cloned()
+            .chain(array(operation.remove("parameters")))
+        {
+            let resolved = self.resolve(&parameter);
+
+            match resolved.get("in").and_then(Value::as_str) {
+                Some("body") => body = Some(resolved),
+                Some("formData") => form.push(resolved),
+                _ => parameters.push(upgrade_parameter(parameter)),
+            }
+        }
+
+        if !parameters.is_empty() {
+            operation.insert("parameters".to_owned(), Value::Array(parameters));
+        }
+
+        let request_body = match body {
+            Some(body) => Some(body_request(body, &consumes)),
+            None if !form.is_empty() => Some(form_request(form, &consumes)),
+            None => None,
+        };
+
+        if let Some(request_body) = request_body {
+            operation.insert("requestBody".to_owned(), request_body);
+        }
+
+        if let Some(Value::Object(responses)) = operation.remove("responses") {
+            let responses = responses
+                .into_iter()
+                .map(|(status, response)| (status, upgrade_response(response, &produces)))
+                .collect::<Map<String, Value>>();
+            operation.insert("responses".to_owned(), Value::Object(responses));
+        }
+
+        Value::Object(operation)
+    }
+
+    /// Looks up a `#/parameters/...` reference. Body and form parameters are inlined
+    /// rather than referenced, as they have no OpenAPI 3.0 parameter to point at.
+    fn resolve(&self, parameter: &Value) -> Value {
+        parameter
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix("#/parameters/"))
+            .and_then(|name| self.parameters.get(name))
+            .unwrap_or(parameter)
+            .to_owned()
+    }
+}
+
+fn is_body(parameter: &Value) -> bool {
+    matches!(
+        parameter.get("in").and_then(Value::as_str),
+        Some("body" | "formData")
+    )
+}
+
+fn body_request(body: Value, consumes: &[String]) -> Value {
+    let schema = upgrade_schema(body.get("schema").cloned().unwrap_or(json!({})));
+    let content = consumes
+        .iter()
+        .map(|media_type| (media_type.to_owned(), json!({ "schema": schema })))
+        .collect::<Map<String, Value>>();
+    let mut request_body = Map::new();
+
+    if let Some(description) = body.get("description") {
+        request_body.insert("description".to_owned(), description.to_owned());
+    }
+
+    request_body.insert("content".to_owned(), Value::Object(content));
+    request_body.insert(
+        "required".to_owned(),
+        body.get("required").cloned().unwrap_or(json!(false)),
+    );
+
+    Value::Object(request_body)
+}
+
+/// Form parameters become the properties of a single object schema. Only form media
+/// types are kept from `consumes`; without one, file parameters call for multipart.
+fn form_request(form: Vec<Value>, consumes: &[String]) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    let mut has_file = false;
+
+    for parameter in form {
+        let Value::Object(mut parameter) = parameter else {
+            continue;
+        };
+        let name = match parameter.remove("name") {
+            Some(Value::String(name)) => name,
+            _ => continue,
+        };
+
+        if parameter.get("required") == Some(&json!(true)) {
+            required.push(json!(name));
+        }
+
+        has_file |= parameter.get("type") == Some(&json!("file"));
+        properties.insert(name, parameter_schema(&mut parameter));
+    }
+
+    let mut schema = Map::new();
+    schema.insert("type".to_owned(), json!("object"));
+    schema.insert("properties".to_owned(), Value::Object(properties));
+
+    if !required.is_empty() {
+        schema.insert("required".to_owned(), Value::Array(required));
+    }
+
+    let mut media_types = consumes
+        .iter()
+        .filter(|media_type| {
+            *media_type == "multipart/form-data"
+                || *media_type == "application/x-www-form-urlencoded"
+        })
+        .cloned()
+        .collect::<Vec<String>>();
+
+    if media_types.is_empty() {
+        media_types.push(match has_file {
+            true => "multipart/form-data".to_owned(),
+            false => "application/x-www-form-urlencoded".to_owned(),
+        });
+    }
+
+    let content = media_types
+        .into_iter()
+        .map(|media_type| (media_type, json!({ "schema": schema })))
+        .collect::<Map<String, Value>>();
+
+    json!({ "content": content })
+}
+
+/// Moves the schema keywords of a non-body parameter into its `schema`, and maps
+/// `collectionFormat` to the matching `style`/`explode`.
+fn upgrade_parameter(parameter: Value) -> Value {
+    let Value::Object(mut parameter) = parameter else {
+        return parameter;
+    };
+
+    if parameter.contains_key("$ref") {
+        return Value::Object(parameter);
+    }
+
+    let schema = parameter_schema(&mut parameter);
+
+    match parameter
+        .remove("collectionFormat")
+        .as_ref()
+        .and_then(Value::as_str)
+    {
+        Some("multi") => {
+            parameter.insert("explode".to_owned(), json!(true));
+        }
+        Some("csv") => {
+            parameter.insert("explode".to_owned(), json!(false));
+        }
+        Some("ssv") => {
+            parameter.insert("style".to_owned(), json!("spaceDelimited"));
+        }
+        Some("pipes") => {
+            parameter.insert("style".to_owned(), json!("pipeDelimited"));
+        }
+        _ => {}
+    }
+
+    parameter.insert("schema".to_owned(), schema);
+
+    Value::Object(parameter)
+}
+
+fn parameter_schema(parameter: &mut Map<String, Value>) -> Value {
+    let schema = SCHEMA_KEYWORDS
+        .iter()
+        .filter_map(|keyword| Some((keyword.to_string(), parameter.remove(*keyword)?)))
+        .collect::<Map<String, Value>>();
+
+    upgrade_schema(Value::Object(schema))
+}
+
+fn upgrade_response(response: Value, produces: &[String]) -> Value {
+    let Value::Object(mut response) = response else {
+        return response;
+    };
+
+    if response.contains_key("$ref") {
+        return Value::Object(response);
+    }
+
+    let examples = object(response.remove("examples"));
+
+    if let Some(schema) = response.remove("schema") {
+        let schema = upgrade_schema(schema);
+        let content = produces
+            .iter()
+            .map(|media_type| {
+                let mut media_type_object = Map::new();
+                media_type_object.insert("schema".to_owned(), schema.to_owned());
+
+                if let Some(example) = examples.get(media_type) {
+                    media_type_object.insert("example".to_owned(), example.to_owned());
+                }
+
+                (media_type.to_owned(), Value::Object(media_type_object))
+            })
+            .collect::<Map<String, Value>>();
+        response.insert("content".to_owned(), Value::Object(content));
+    }
+
+    if let Some(Value::Object(headers)) = response.remove("headers") {
+        let headers = headers
+            .into_iter()
+            .map(|(name, header)| {
+                let Value::Object(mut header) = header else {
+                    return (name, header);
+                };
+                let schema = parameter_schema(&mut header);
+                header.remove("collectionFormat");
+                header.insert("schema".to_owned(), schema);
+
+                (name, Value::Object(header))
+            })
+            .collect::<Map<String, Value>>();
+        response.insert("headers".to_owned(), Value::Object(headers));
+    }
+
+    Value::Object(response)
+}
+
+/// Rewrites the Swagger 2.0 schema keywords OpenAPI 3.0 spells differently: `file`
+/// types, `x-nullable` and string discriminators.
+fn upgrade_schema(schema: Value) -> Value {
+    let Value::Object(mut schema) = schema else {
+        return schema;
+    };
+
+    if schema.get("type") == Some(&json!("file")) {
+        schema.insert("type".to_owned(), json!("string"));
+        schema.insert("format".to_owned(), json!("binary"));
+    }
+
+    if let Some(nullable) = schema.remove("x-nullable") {
+        schema.insert("nullable".to_owned(), nullable);
+    }
+
+    if let Some(Value::String(property_name)) = schema.remove("discriminator") {
+        schema.insert(
+            "discriminator".to_owned(),
+            json!({ "propertyName": property_name }),
+        );
+    }
+
+    schema
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match (key.as_str(), value) {
+                ("items" | "additionalProperties" | "not", value) => upgrade_schema(value),
+                ("allOf" | "oneOf" | "anyOf", Value::Array(schemas)) => {
+                    Value::Array(schemas.into_iter().map(upgrade_schema).collect())
+                }
+                ("properties", Value::Object(properties)) => Value::Object(
+                    properties
+                        .into_iter()
+                        .map(|(name, schema)| (name, upgrade_schema(schema)))
+                        .collect(),
+                ),
+                (_, value) => value,
+            };
+
+            (key, value)
+        })
+        .collect::<Map<String, Value>>()
+        .into()
+}
+
+fn servers(swagger: &mut Map<String, Value>) -> Vec<Value> {
+    let host = swagger.remove("host");
+    let base_path = swagger.remove("basePath");
+    let base_path = base_path.as_ref().and_then(Value::as_str).unwrap_or("");
+    let schemes = media_types(swagger.remove("schemes")).unwrap_or_default();
+
+    match host.as_ref().and_then(Value::as_str) {
+        Some(host) if schemes.is_empty() => vec![json!({ "url": format!("//{host}{base_path}") })],
+        Some(host) => schemes
+            .iter()
+            .map(|scheme| json!({ "url": format!("{scheme}://{host}{base_path}") }))
+            .collect(),
+        None if base_path.is_empty() => Vec::new(),
+        None => vec![json!({ "url": base_path })],
+    }
+}
+
+/// Points `$ref`s at the locations their targets moved to under `components`.
+fn rewrite_refs(value: Value) -> Value {
+    match value {
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(key, value)| match (key.as_str(), value) {
+                ("$ref", Value::String(reference)) => (key, json!(rewrite_ref(&reference))),
+                (_, value) => (key, rewrite_refs(value)),
+            })
+            .collect::<Map<String, Value>>()
+            .into(),
+        Value::Array(values) => Value::Array(values.into_iter().map(rewrite_refs).collect()),
+        value => value,
+    }
+}
+
+fn rewrite_ref(reference: &str) -> String {
+    let Some((document, pointer)) = reference.split_once('#') else {
+        return reference.to_owned();
+    };
+
+    for (from, to) in [
+        ("/definitions/", "/components/schemas/"),
+        ("/parameters/", "/components/parameters/"),
+        ("/responses/", "/components/responses/"),
+    ] {
+        if let Some(name) = pointer.strip_prefix(from) {
+            return format!("{document}#{to}{name}");
+        }
+    }
+
+    reference.to_owned()
+}
+
+/// Reads a list of strings such as `consumes`, `produces` or `schemes`.
+fn media_types(value: Option<Value>) -> Option<Vec<String>> {
+    match value {
+        Some(Value::Array(values)) if !values.is_empty() => Some(
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_owned))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn object(value: Option<Value>) -> Map<String, Value> {
+    match value {
+        Some(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+fn array(value: Option<Value>) -> Vec<Value> {
+    match value {
+        Some(Value::Array(values)) => values,
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,313 @@
+use crate::config::Source;
+use crate::document::parse_document;
+use crate::error::{Error, Result};
+use crate::fetch::fetch_text;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::Deserialize;
+use url::Url;
+
+/// A spec served by a Swagger UI, named after its springdoc group if it has one.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct SpecUrl {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub url: String,
+}
+
+impl SpecUrl {
+    /// The Swagger UI page showing this spec: grouped specs are picked with
+    /// `urls.primaryName`.
+    pub fn ui_url(&self, swagger_ui_url: &str) -> String {
+        let Some(name) = &self.name else {
+            return swagger_ui_url.to_owned();
+        };
+        let separator = match swagger_ui_url.contains('?') {
+            true => '&',
+            false => '?',
+        };
+
+        format!(
+            "{swagger_ui_url}{separator}urls.primaryName={}",
+            utf8_percent_encode(name, NON_ALPHANUMERIC)
+        )
+    }
+}
+
+/// What a Swagger UI is configured with, as springdoc serves it from
+/// `/v3/api-docs/swagger-config`.
+#[derive(Deserialize, Debug, Default)]
+struct SwaggerConfig {
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    urls: Vec<SpecUrl>,
+}
+
+/// Finds the specs the Swagger UI at `swagger_ui_url` shows, one per springdoc group.
+/// springdoc's `swagger-config` is tried first, then the page itself and the
+/// `swagger-initializer.js` it loads, looking for `configUrl`, `urls` or `url`.
+pub async fn discover(source: &Source, swagger_ui_url: &str) -> Result<Vec<SpecUrl>> {
+    if let Some(config_url) = springdoc_config_url(swagger_ui_url) {
+        if let Ok(specs) = from_swagger_config(source, &config_url, swagger_ui_url).await {
+            return Ok(specs);
+        }
+    }
+
+    let page = fetch_text(source, swagger_ui_url, swagger_ui_url).await?;
+
+    if let Some(specs) = from_script(source, &page, swagger_ui_url, swagger_ui_url).await? {
+        return Ok(specs);
+    }
+
+    for script in attributes(&page, "src").filter(|src| src.contains("initializer")) {
+        let script_url = join(swagger_ui_url, script);
+        let script = fetch_text(source, &script_url, swagger_ui_url).await?;
+
+        if let Some(specs) = from_script(source, &script, &script_url, swagger_ui_url).await? {
+            return Ok(specs);
+        }
+    }
+
+    Err(Error::parse(swagger_ui_url, "no spec URL found"))
+}
+
+/// springdoc serves its `swagger-config` next to the specs, above `/swagger-ui`.
+fn springdoc_config_url(swagger_ui_url: &str) -> Option<String> {
+    let config_url = match swagger_ui_url.split_once("/swagger-ui") {
+        Some((base, _)) => format!("{base}/v3/api-docs/swagger-config"),
+        None => join(swagger_ui_url, "/v3/api-docs/swagger-config"),
+    };
+
+    Url::parse(&config_url).ok().map(String::from)
+}
+
+async fn from_swagger_config(
+    source: &Source,
+    config_url: &str,
+    swagger_ui_url: &str,
+) -> Result<Vec<SpecUrl>> {
+    let text = fetch_text(source, config_url, swagger_ui_url).await?;
+    let config: SwaggerConfig = serde_json::from_value(parse_document(config_url, &text)?)
+        .map_err(|e| Error::parse(config_url, e))?;
+    let specs = match (config.urls.is_empty(), config.url) {
+        (false, _) => config.urls,
+        (true, Some(url)) => vec![SpecUrl { name: None, url }],
+        (true, None) => return Err(Error::parse(config_url, "no spec URL found")),
+    };
+
+    Ok(absolute(specs, config_url))
+}
+
+/// Reads the `SwaggerUIBundle` options of a page or script found at `location`.
+async fn from_script(
+    source: &Source,
+    script: &str,
+    location: &str,
+    swagger_ui_url: &str,
+) -> Result<Option<Vec<SpecUrl>>> {
+    if let Some(config_url) = properties(script, "configUrl").find_map(string_literal) {
+        let config_url = join(location, config_url);
+
+        return from_swagger_config(source, &config_url, swagger_ui_url)
+            .await
+            .map(Some);
+    }
+
+    let urls = properties(script, "urls")
+        .filter_map(|value| value.strip_prefix('['))
+        .find_map(|value| value.split_once(']'))
+        .map(|(urls, _)| {
+            urls.split('}')
+                .filter_map(|entry| {
+                    let url = properties(entry, "url").find_map(string_literal)?;
+                    let name = properties(entry, "name").find_map(string_literal);
+
+                    Some(SpecUrl {
+                        name: name.map(str::to_owned),
+                        url: url.to_owned(),
+                    })
+                })
+                .collect::<Vec<SpecUrl>>()
+        })
+        .filter(|urls| !urls.is_empty());
+    let specs = match urls {
+        Some(urls) => urls,
+        None => match properties(script, "url").find_map(string_literal) {
+            Some(url) => vec![SpecUrl {
+                name: None,
+                url: url.to_owned(),
+            }],
+            None => return Ok(None),
+        },
+    };
+
+    Ok(Some(absolute(specs, location)))
+}
+
+fn absolute(specs: Vec<SpecUrl>, location: &str) -> Vec<SpecUrl> {
+    specs
+        .into_iter()
+        .map(|spec| SpecUrl {
+            url: join(location, &spec.url),
+            ..spec
+        })
+        .collect()
+}
+
+fn join(base: &str, reference: &str) -> String {
+    Url::parse(base)
+        .and_then(|base| base.join(reference))
+        .map(String::from)
+        .unwrap_or(reference.to_owned())
+}
+
+/// What follows each `key: ` in a script, where the key may be quoted and must not be
+/// part of a longer name (`url` does not match `urls` or `configUrl`).
+fn properties<'a>(script: &'a str, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    script.match_indices(key).filter_map(move |(start, _)| {
+        let before = script[..start].chars().next_back();
+
+        if before.is_some_and(|c| c.is_alphanumeric() || "_.$".contains(c)) {
+            return None;
+        }
+
+        let rest = script[start + key.len()..].trim_start_matches(['"', '\'']);
+
+        Some(rest.trim_start().strip_prefix(':')?.trim_start())
+    })
+}
+
+/// Values of an HTML attribute, e.g. the `src` of each `<script>`.
+fn attributes<'a>(html: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    html.match_indices(name).filter_map(move |(start, _)| {
+        let before = html[..start].chars().next_back()?;
+
+        if !before.is_whitespace() {
+            return None;
+        }
+
+        let rest = html[start + name.len()..].trim_start();
+
+        string_literal(rest.strip_prefix('=')?.trim_start())
+    })
+}
+
+/// The contents of the string literal `value` starts with.
+fn string_literal(value: &str) -> Option<&str> {
+    let quote = value.chars().next().filter(|c| "\"'`".contains(*c))?;
+    let value = &value[1..];
+
+    value.find(quote).map(|end| &value[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    async fn serve(server: &MockServer, route: &str, body: &str) {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn discover_springdoc_groups() {
+        let server = MockServer::start().await;
+        serve(
+            &server,
+            "/api/v3/api-docs/swagger-config",
+            r#"{
+                "configUrl": "/api/v3/api-docs/swagger-config",
+                "urls": [
+                    {"url": "/api/v3/api-docs/order", "name": "order"},
+                    {"url": "/api/v3/api-docs/pet store", "name": "pet store"}
+                ]
+            }"#,
+        )
+        .await;
+        let swagger_ui_url = format!("{}/api/swagger-ui/index.html", server.uri());
+        let specs = discover(&Source::default(), &swagger_ui_url).await.unwrap();
+
+        assert_eq!(
+            specs,
+            vec![
+                SpecUrl {
+                    name: Some("order".to_owned()),
+                    url: format!("{}/api/v3/api-docs/order", server.uri()),
+                },
+                SpecUrl {
+                    name: Some("pet store".to_owned()),
+                    url: format!("{}/api/v3/api-docs/pet%20store", server.uri()),
+                },
+            ]
+        );
+        assert_eq!(
+            specs[1].ui_url(&swagger_ui_url),
+            format!("{swagger_ui_url}?urls.primaryName=pet%20store")
+        );
+    }
+
+    #[tokio::test]
+    async fn discover_from_swagger_initializer() {
+        let server = MockServer::start().await;
+        serve(
+            &server,
+            "/docs/index.html",
+            r#"<script src="./swagger-ui-bundle.js"></script>
+            <script src="./swagger-initializer.js" charset="UTF-8"></script>"#,
+        )
+        .await;
+        serve(
+            &server,
+            "/docs/swagger-initializer.js",
+            r#"window.onload = function() {
+                window.ui = SwaggerUIBundle({
+                    url: "openapi.yaml",
+                    dom_id: '#swagger-ui',
+                    oauth2RedirectUrl: window.location.origin + "/oauth2-redirect.html",
+                });
+            };"#,
+        )
+        .await;
+        let swagger_ui_url = format!("{}/docs/index.html", server.uri());
+
+        assert_eq!(
+            discover(&Source::default(), &swagger_ui_url).await.unwrap(),
+            vec![SpecUrl {
+                name: None,
+                url: format!("{}/docs/openapi.yaml", server.uri()),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn read_inline_urls() {
+        let script = r#"SwaggerUIBundle({
+            urls: [{url: "/v3/api-docs/a", name: "a"}, {name: 'b', "url": '/v3/api-docs/b'}],
+            "urls.primaryName": "b",
+        })"#;
+        let specs = from_script(&Source::default(), script, "http://localhost/", "")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            specs,
+            Some(vec![
+                SpecUrl {
+                    name: Some("a".to_owned()),
+                    url: "http://localhost/v3/api-docs/a".to_owned(),
+                },
+                SpecUrl {
+                    name: Some("b".to_owned()),
+                    url: "http://localhost/v3/api-docs/b".to_owned(),
+                },
+            ])
+        );
+    }
+}
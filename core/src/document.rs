@@ -0,0 +1,60 @@
+use crate::error::{Error, Result};
+use serde_json::Value;
+
+/// Serialization formats a spec can be written in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    /// Picks the format from the extension of `location` (a path or URL), falling back
+    /// to sniffing `text`: JSON documents start with `{`.
+    pub fn detect(location: &str, text: &str) -> Self {
+        let path = location.split(['?', '#']).next().unwrap_or_default();
+        let extension = path.rsplit_once('.').map(|(_, extension)| extension);
+
+        match extension {
+            Some("json") => Format::Json,
+            Some("yaml" | "yml") => Format::Yaml,
+            _ if text.trim_start().starts_with('{') => Format::Json,
+            _ => Format::Yaml,
+        }
+    }
+}
+
+/// Parses a JSON or YAML spec read from `location` into a JSON value.
+pub fn parse_document(location: &str, text: &str) -> Result<Value> {
+    match Format::detect(location, text) {
+        Format::Json => serde_json::from_str(text).map_err(|e| Error::parse(location, e)),
+        Format::Yaml => serde_yaml::from_str(text).map_err(|e| Error::parse(location, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_format() {
+        assert_eq!(Format::detect("openapi.yaml", "{}"), Format::Yaml);
+        assert_eq!(Format::detect("openapi.yml", ""), Format::Yaml);
+        assert_eq!(
+            Format::detect("openapi.json", "openapi: 3.0.3"),
+            Format::Json
+        );
+        assert_eq!(
+            Format::detect("http://localhost/v3/api-docs", r#" {"openapi": "3.0.3"}"#),
+            Format::Json
+        );
+        assert_eq!(
+            Format::detect("http://localhost/v3/api-docs.yaml?group=pet", "{}"),
+            Format::Yaml
+        );
+        assert_eq!(
+            Format::detect("http://localhost/v3/api-docs", "openapi: 3.0.3"),
+            Format::Yaml
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+type Source = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The spec or config could not be read from its URL or file.
+    #[error("could not fetch {location}: {source}")]
+    Fetch {
+        location: String,
+        #[source]
+        source: Source,
+    },
+    /// The spec or config was read but is not a valid document.
+    #[error("could not parse {location}: {source}")]
+    Parse {
+        location: String,
+        #[source]
+        source: Source,
+    },
+    /// A schema Humbler does not know how to render.
+    #[error("unsupported schema {schema}: {reason}")]
+    UnsupportedSchema { schema: String, reason: String },
+    /// A field Humbler needs is absent, located by its JSON pointer, e.g.
+    /// `/paths/~1pet/post/operationId`.
+    #[error("missing field at {pointer}")]
+    MissingField { pointer: String },
+    /// A `$ref` that does not lead to an item.
+    #[error("could not resolve {reference}: {reason}")]
+    Reference { reference: String, reason: String },
+}
+
+impl Error {
+    pub(crate) fn fetch(location: &str, source: impl Into<Source>) -> Self {
+        Self::Fetch {
+            location: location.to_owned(),
+            source: source.into(),
+        }
+    }
+
+    pub(crate) fn parse(location: &str, source: impl Into<Source>) -> Self {
+        Self::Parse {
+            location: location.to_owned(),
+            source: source.into(),
+        }
+    }
+
+    pub(crate) fn reference(reference: &str, reason: impl Into<String>) -> Self {
+        Self::Reference {
+            reference: reference.to_owned(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// An operation left out of the table in lenient mode, located by its JSON pointer. A
+/// whole service left out of a catalogue has an empty pointer.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Warning {
+    /// The service the operation belongs to, when several are merged.
+    #[serde(default)]
+    pub service: Option<String>,
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(service) = &self.service {
+            write!(f, "{service}: ")?;
+        }
+
+        match self.pointer.is_empty() {
+            true => write!(f, "{}", self.message),
+            false => write!(f, "{}: {}", self.pointer, self.message),
+        }
+    }
+}
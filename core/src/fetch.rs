@@ -0,0 +1,168 @@
+use crate::config::Source;
+use crate::error::{Error, Result};
+use reqwest::{Certificate, Client};
+use std::{fs, time::Duration};
+use url::Url;
+
+/// Fetches `url` with the `[source]` settings. Credentials and headers are only sent
+/// when `url` shares its origin with `spec_url`, so a `$ref` into another host does not
+/// get them.
+pub async fn fetch_text(source: &Source, url: &str, spec_url: &str) -> Result<String> {
+    let fetch = |e| Error::fetch(url, e);
+    let mut request = client(source, url)?.get(url);
+
+    if same_origin(url, spec_url) {
+        if let Some(token) = &source.bearer_token {
+            request = request.bearer_auth(token);
+        } else if let Some(basic) = &source.basic_auth {
+            request = request.basic_auth(&basic.username, basic.password.as_ref());
+        }
+
+        for (name, value) in &source.headers {
+            request = request.header(name, value);
+        }
+    }
+
+    let response = request.send().await.map_err(fetch)?;
+    let response = response.error_for_status().map_err(fetch)?;
+
+    response.text().await.map_err(fetch)
+}
+
+fn client(source: &Source, url: &str) -> Result<Client> {
+    let mut builder = Client::builder().danger_accept_invalid_certs(source.accept_invalid_certs);
+
+    if let Some(timeout) = source.timeout_secs {
+        builder = builder.timeout(Duration::from_secs(timeout));
+    }
+
+    if let Some(path) = &source.ca_cert {
+        let location = path.display().to_string();
+        let pem = fs::read(path).map_err(|e| Error::fetch(&location, e))?;
+        let certificate = Certificate::from_pem(&pem).map_err(|e| Error::parse(&location, e))?;
+        builder = builder.add_root_certificate(certificate);
+    }
+
+    builder.build().map_err(|e| Error::fetch(url, e))
+}
+
+fn same_origin(url: &str, spec_url: &str) -> bool {
+    match (Url::parse(url), Url::parse(spec_url)) {
+        (Ok(url), Ok(spec_url)) => url.origin() == spec_url.origin(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BasicAuth;
+    use indexmap::IndexMap;
+    use wiremock::{
+        matchers::{header, header_exists, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const SPEC: &str = r#"{"openapi": "3.0.3"}"#;
+
+    async fn gateway() -> MockServer {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v3/api-docs"))
+            .and(header("Authorization", "Bearer secret"))
+            .and(header("X-Tenant", "acme"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SPEC))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/basic"))
+            .and(header("Authorization", "Basic dXNlcjpwYXNz"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SPEC))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/slow"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(SPEC)
+                    .set_delay(Duration::from_secs(2)),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        server
+    }
+
+    #[tokio::test]
+    async fn fetch_with_credentials() {
+        let server = gateway().await;
+        let url = format!("{}/v3/api-docs", server.uri());
+        let source = Source {
+            bearer_token: Some("secret".to_owned()),
+            headers: IndexMap::from([("X-Tenant".to_owned(), "acme".to_owned())]),
+            ..Source::default()
+        };
+
+        assert_eq!(fetch_text(&source, &url, &url).await.unwrap(), SPEC);
+        assert!(matches!(
+            fetch_text(&Source::default(), &url, &url).await,
+            Err(Error::Fetch { .. })
+        ));
+
+        let url = format!("{}/basic", server.uri());
+        let source = Source {
+            basic_auth: Some(BasicAuth {
+                username: "user".to_owned(),
+                password: Some("pass".to_owned()),
+            }),
+            ..Source::default()
+        };
+
+        assert_eq!(fetch_text(&source, &url, &url).await.unwrap(), SPEC);
+    }
+
+    #[tokio::test]
+    async fn keep_credentials_to_spec_origin() {
+        let server = gateway().await;
+        let other = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(header_exists("Authorization"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&other)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SPEC))
+            .mount(&other)
+            .await;
+
+        let spec_url = format!("{}/v3/api-docs", server.uri());
+        let source = Source {
+            bearer_token: Some("secret".to_owned()),
+            ..Source::default()
+        };
+        let url = format!("{}/common.json", other.uri());
+
+        assert_eq!(fetch_text(&source, &url, &spec_url).await.unwrap(), SPEC);
+    }
+
+    #[tokio::test]
+    async fn time_out_slow_sources() {
+        let server = gateway().await;
+        let url = format!("{}/slow", server.uri());
+        let source = Source {
+            timeout_secs: Some(1),
+            ..Source::default()
+        };
+
+        assert!(matches!(
+            fetch_text(&source, &url, &url).await,
+            Err(Error::Fetch { .. })
+        ));
+    }
+}
@@ -1,32 +1,155 @@
-use crate::config::load_config;
-use crate::utils::option::OptionExt;
-use anyhow::Result;
+use crate::bundle::{bundle, Bundle};
+use crate::config::{load_config, Config, LinkStyle, ResponseMode};
+use crate::convert::normalize;
+use crate::document::parse_document;
+use crate::error::{Error, Result, Warning};
+use crate::fetch::fetch_text;
+use crate::utils::{
+    openapi::{component_name, pointer_token},
+    option::OptionExt,
+    ReferenceOrExt,
+};
 use indexmap::IndexMap;
 use openapiv3::{
-    ArrayType, Components, MediaType, ObjectType, OpenAPI, Parameter, ReferenceOr, Responses,
-    Schema, SchemaKind,
+    AdditionalProperties, AnySchema, ArrayType, Components, Discriminator, Encoding, IntegerType,
+    MediaType, NumberType, ObjectType, OpenAPI, Operation, Parameter, ParameterSchemaOrContent,
+    PathItem, ReferenceOr, Responses, Schema, SchemaKind, StringType, VariantOrUnknownOrEmpty,
 };
-use reqwest::Error;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
-use std::hash::RandomState;
+use std::{collections::HashMap, fmt, hash::RandomState, mem};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ApiInfo {
+    /// The service the operation belongs to, when several are merged.
+    #[serde(default)]
+    pub service: Option<String>,
+    /// The path, or `webhook {name}` for webhooks.
     pub path: String,
     pub method: String,
-    pub parameters: Vec<(String, Value)>,
-    pub request_body: Option<String>,
-    pub response: Option<String>,
+    pub operation_id: String,
+    /// The first tag of the operation, or `"untagged"`.
+    pub tag: String,
+    pub parameters: Vec<ParameterInfo>,
+    pub request_body: Content,
+    pub responses: IndexMap<String, ResponseInfo>,
     pub swagger_url: String,
 }
 
-#[derive(Debug)]
+/// Rendered schema per media type, e.g. `application/json` → `{"id":"integer"}`.
+pub type Content = IndexMap<String, String>;
+
+/// Renders a body for a markdown cell. Media types sharing the same shape are grouped,
+/// and they are only labelled when the shape differs between media types.
+pub fn render_content(content: &Content) -> String {
+    let mut groups = IndexMap::<&String, Vec<&str>>::new();
+
+    for (media_type, body) in content {
+        groups.entry(body).or_default().push(media_type);
+    }
+
+    match groups.len() {
+        0 => String::new(),
+        1 => groups.into_keys().map(String::to_owned).collect(),
+        _ => groups
+            .into_iter()
+            .map(|(body, media_types)| format!("{}: {body}", media_types.join(", ")))
+            .collect::<Vec<String>>()
+            .join("<br>"),
+    }
+}
+
+impl ApiInfo {
+    /// Renders the responses kept by `mode` for a markdown cell, falling back to the
+    /// `default` response when no 2xx one is declared. A single response is rendered as
+    /// its bare body; several are labelled with their status code and description, one
+    /// per line.
+    pub fn render_responses(&self, mode: ResponseMode) -> String {
+        let is_success = |status: &str| status.starts_with('2');
+        let has_success = self.responses.keys().any(|status| is_success(status));
+        let responses = self
+            .responses
+            .iter()
+            .filter(|(status, _)| match mode {
+                ResponseMode::All => true,
+                ResponseMode::Success if has_success => is_success(status),
+                ResponseMode::Success => *status == "default",
+            })
+            .collect::<Vec<_>>();
+
+        match responses.as_slice() {
+            [] => String::new(),
+            [(_, response)] if mode == ResponseMode::Success => render_content(&response.content),
+            responses => responses
+                .iter()
+                .map(|(status, response)| match response.content.is_empty() {
+                    false => format!(
+                        "{status} ({}): {}",
+                        response.description.trim(),
+                        render_content(&response.content)
+                    ),
+                    true => format!("{status} ({})", response.description.trim()),
+                })
+                .collect::<Vec<String>>()
+                .join("<br>"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ResponseInfo {
+    pub description: String,
+    pub content: Content,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ParameterInfo {
+    pub name: String,
+    pub location: ParameterLocation,
+    pub required: bool,
+    pub schema: Value,
+    /// Set when the parameter is described by `content` rather than `schema`.
+    pub media_type: Option<String>,
+}
+
+impl fmt::Display for ParameterInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let optional = if self.required { "" } else { "?" };
+        let location = match self.location {
+            ParameterLocation::Path | ParameterLocation::Query => "",
+            ParameterLocation::Header => "header ",
+            ParameterLocation::Cookie => "cookie ",
+        };
+
+        write!(f, r#"{location}"{}{optional}""#, self.name)?;
+
+        match &self.media_type {
+            Some(media_type) => write!(f, " ({media_type}): {}", self.schema),
+            None => write!(f, ": {}", self.schema),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterLocation {
+    Path,
+    Query,
+    Header,
+    Cookie,
+}
+
+#[derive(Debug, Clone)]
 pub struct Humbler {
     swagger_ui_url: String,
     openapi_json_url: String,
     filter_keywords: Vec<String>,
+    config: Config,
+    service: Option<String>,
     pub api_infos: Vec<ApiInfo>,
+    /// Operations skipped in lenient mode.
+    pub warnings: Vec<Warning>,
 }
 
 impl Humbler {
@@ -35,214 +158,566 @@ impl Humbler {
             swagger_ui_url,
             openapi_json_url,
             filter_keywords: Vec::new(),
+            config: Config::default(),
+            service: None,
             api_infos: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn with_config(self, config: Config) -> Self {
+        Self { config, ..self }
+    }
+
+    /// Names the service the spec describes, rendered in its own column.
+    pub fn with_service(self, service: String) -> Self {
+        Self {
+            service: Some(service),
+            ..self
         }
     }
 
     pub async fn filter_on(&self) -> Result<Self> {
         let config = load_config(".humbler.toml")?;
         let humbler = Self {
-            swagger_ui_url: self.swagger_ui_url.clone(),
-            openapi_json_url: self.openapi_json_url.clone(),
-            filter_keywords: config.filter_keywords,
+            filter_keywords: config.filter_keywords.clone(),
+            config,
             api_infos: Vec::new(),
+            warnings: Vec::new(),
+            ..self.clone()
         };
 
         humbler.run().await
     }
 
     pub async fn search(&self, keyword: String) -> Result<Self> {
-        let mut humbler = Self {
-            swagger_ui_url: self.swagger_ui_url.clone(),
-            openapi_json_url: self.openapi_json_url.clone(),
+        let humbler = Self {
             filter_keywords: vec![keyword],
             api_infos: Vec::new(),
+            warnings: Vec::new(),
+            ..self.clone()
         };
 
         humbler.run().await
     }
 
     pub async fn run(self) -> Result<Self> {
+        let (api_infos, warnings) = self.get_api_infos().await?;
+
         Ok(Self {
-            swagger_ui_url: self.swagger_ui_url.clone(),
-            openapi_json_url: self.openapi_json_url.clone(),
-            filter_keywords: self.filter_keywords.clone(),
-            api_infos: self.get_api_infos().await?,
+            api_infos,
+            warnings,
+            ..self
         })
     }
 
-    async fn get_api_infos(&self) -> Result<Vec<ApiInfo>, anyhow::Error> {
+    /// Renders every operation matching the filter keywords. In lenient mode an operation
+    /// that cannot be rendered becomes a warning instead of failing the run.
+    async fn get_api_infos(&self) -> Result<(Vec<ApiInfo>, Vec<Warning>)> {
         let openapi = self.get_openapi().await?;
-        openapi
+        let components = openapi.components.unwrap_or_default();
+        let webhooks = match openapi.extensions.get("x-webhooks") {
+            Some(webhooks) => serde_json::from_value(webhooks.to_owned())
+                .map_err(|e| Error::parse(&self.openapi_json_url, e))?,
+            None => IndexMap::<String, ReferenceOr<PathItem>>::new(),
+        };
+        let operations = openapi
             .paths
             .into_iter()
-            .filter(|(path, _)| {
+            .map(|(path, path_item)| (format!("/paths/{}", pointer_token(&path)), path, path_item))
+            .chain(webhooks.into_iter().map(|(name, path_item)| {
+                let pointer = format!("/webhooks/{}", pointer_token(&name));
+
+                (pointer, format!("webhook {name}"), path_item)
+            }))
+            .filter(|(_, path, _)| {
                 self.filter_keywords
                     .iter()
                     .all(|keyword| path.contains(keyword))
             })
-            .filter(|(_, reference_or_path_item)| reference_or_path_item.as_item().is_some())
-            .map(|(path, reference_or_path_item)| {
-                let path_item = reference_or_path_item
-                    .into_item()
-                    .to_result("PathItem not found")?;
-
-                Ok(path_item.into_iter().map({
-                    let components = openapi
-                        .components
-                        .as_ref()
-                        .to_result("Components not found")?;
-
-                    move |(method, operation)| {
-                        let operation_id =
-                            operation.operation_id.to_result("OperationId not found")?;
-                        let tag = operation
-                            .tags
-                            .into_iter()
-                            .next()
-                            .to_result("Tag not found")?;
-                        let swagger_url = format!("{}/{tag}/{operation_id}", self.swagger_ui_url);
-                        let parameters = operation
-                            .parameters
-                            .into_iter()
-                            .filter_map(|param| {
-                                let param = param.into_item()?;
-                                match param {
-                                    Parameter::Query { parameter_data, .. }
-                                    | Parameter::Path { parameter_data, .. } => {
-                                        let name = parameter_data.name;
-                                        let schema_type = match parameter_data.format {
-                                            openapiv3::ParameterSchemaOrContent::Schema(schema) => {
-                                                Parser::new().parse_schema(components, schema)
-                                            }
-                                            openapiv3::ParameterSchemaOrContent::Content(_) => {
-                                                todo!()
-                                            }
-                                        };
-
-                                        Some(schema_type.map(|schema_type| (name, schema_type)))
-                                    }
-                                    // skip header parameters for now, no todo
-                                    Parameter::Header { .. } => None,
-                                    _ => {
-                                        todo!()
-                                    }
-                                }
-                            })
-                            .collect::<Result<Vec<(String, Value)>>>()?;
-                        let request_body = operation
-                            .request_body
-                            .and_then(|request_body| {
-                                let content = request_body.into_item()?.content;
-
-                                content_to_value(content, components)
-                            })
-                            .transpose()?;
-
-                        let Responses { responses, .. } = operation.responses;
-
-                        let response = responses
-                            .into_iter()
-                            .map(|(_, response)| {
-                                let content = response.into_item()?.content;
-
-                                content_to_value(content, components)
-                            })
-                            .next()
-                            .flatten()
-                            .transpose()?;
-
-                        Ok(ApiInfo {
-                            path: path.clone(),
-                            method: method.to_string(),
-                            parameters,
-                            request_body,
-                            response, // if response has only Description:OK, then it is None for now
-                            swagger_url,
+            .flat_map(|(pointer, path, ref_or_path_item)| match ref_or_path_item {
+                ReferenceOr::Item(path_item) => {
+                    let shared = path_item
+                        .parameters
+                        .iter()
+                        .enumerate()
+                        .map(|(index, param)| {
+                            (format!("{pointer}/parameters/{index}"), param.to_owned())
                         })
+                        .collect::<Vec<_>>();
+
+                    path_item
+                        .into_iter()
+                        .map(|(method, operation)| {
+                            let pointer = format!("{pointer}/{method}");
+                            let api_info = self.to_api_info(
+                                &components,
+                                &path,
+                                method,
+                                operation,
+                                &pointer,
+                                &shared,
+                            );
+
+                            (pointer, api_info)
+                        })
+                        .collect()
+                }
+                ReferenceOr::Reference { reference } => {
+                    let error = Error::reference(&reference, "path items cannot be shared");
+
+                    vec![(pointer, Err(error))]
+                }
+            });
+
+        let mut api_infos = Vec::new();
+        let mut warnings = Vec::new();
+
+        for (pointer, api_info) in operations {
+            match api_info {
+                Ok(api_info) => api_infos.push(api_info),
+                Err(e) if self.config.lenient => warnings.push(Warning {
+                    service: self.service.to_owned(),
+                    pointer,
+                    message: e.to_string(),
+                }),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok((api_infos, warnings))
+    }
+
+    /// Renders an operation. The `shared` parameters of its path item, along with their
+    /// pointers, apply unless the operation redefines them (by name and location).
+    fn to_api_info(
+        &self,
+        components: &Components,
+        path: &str,
+        method: &str,
+        operation: Operation,
+        pointer: &str,
+        shared: &[(String, ReferenceOr<Parameter>)],
+    ) -> Result<ApiInfo> {
+        let config = &self.config;
+        let tag = operation.tags.into_iter().next();
+        let swagger_url = deep_link(
+            config.link_style,
+            &self.swagger_ui_url,
+            tag.as_deref(),
+            operation.operation_id.as_deref(),
+            method,
+            path,
+        );
+        let operation_id = operation
+            .operation_id
+            .unwrap_or_else(|| fallback_operation_id(method, path));
+        let tag = tag.unwrap_or(UNTAGGED.to_owned());
+        let resolve = |(pointer, param): (String, ReferenceOr<Parameter>)| {
+            Ok((pointer, param.resolve(components)?))
+        };
+        let own = operation
+            .parameters
+            .into_iter()
+            .enumerate()
+            .map(|(index, param)| (format!("{pointer}/parameters/{index}"), param))
+            .map(resolve)
+            .collect::<Result<Vec<(String, Parameter)>>>()?;
+        let inherited = shared
+            .iter()
+            .cloned()
+            .map(resolve)
+            .collect::<Result<Vec<(String, Parameter)>>>()?
+            .into_iter()
+            .filter(|(_, param)| !own.iter().any(|(_, own)| is_same_parameter(own, param)))
+            .collect::<Vec<_>>();
+        let parameters = inherited
+            .into_iter()
+            .chain(own)
+            .filter_map(|(pointer, param)| {
+                let location = match param {
+                    Parameter::Query { .. } => ParameterLocation::Query,
+                    Parameter::Header { .. } => ParameterLocation::Header,
+                    Parameter::Path { .. } => ParameterLocation::Path,
+                    Parameter::Cookie { .. } => ParameterLocation::Cookie,
+                };
+                let parameter_data = param.parameter_data();
+
+                if location == ParameterLocation::Header
+                    && config.is_hidden_header(&parameter_data.name)
+                {
+                    return None;
+                }
+
+                let name = parameter_data.name;
+                let required = parameter_data.required;
+                let schema_type = match parameter_data.format {
+                    ParameterSchemaOrContent::Schema(schema) => {
+                        Parser::new(config, Direction::Request)
+                            .parse_schema(components, schema)
+                            .map(|schema| (schema, None))
                     }
+                    ParameterSchemaOrContent::Content(content) => content
+                        .into_iter()
+                        .next()
+                        .or_missing(format!("{pointer}/content"))
+                        .and_then(|(media_type_name, media_type)| {
+                            media_type_to_value(
+                                &media_type_name,
+                                media_type,
+                                components,
+                                config,
+                                Direction::Request,
+                            )
+                            .map(|schema| (schema, Some(media_type_name)))
+                        }),
+                };
+
+                Some(schema_type.map(|(schema, media_type)| ParameterInfo {
+                    name,
+                    location,
+                    required,
+                    schema,
+                    media_type,
                 }))
             })
-            .collect::<Result<Vec<_>>>()? // TODO: decrease collecting to once
+            .collect::<Result<Vec<ParameterInfo>>>()?;
+        let request_body = operation
+            .request_body
+            .map(|request_body| request_body.resolve(components))
+            .transpose()?
+            .map(|request_body| {
+                content_to_values(request_body.content, components, config, Direction::Request)
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let Responses {
+            default, responses, ..
+        } = operation.responses;
+
+        let responses = responses
             .into_iter()
-            .flatten()
-            .collect::<Result<Vec<ApiInfo>>>()
+            .map(|(status, response)| (status.to_string(), response))
+            .chain(default.map(|response| ("default".to_owned(), response)))
+            .map(|(status, response)| {
+                let response = response.resolve(components)?;
+                let content =
+                    content_to_values(response.content, components, config, Direction::Response)?;
+                let description = response.description;
+
+                Ok((
+                    status,
+                    ResponseInfo {
+                        description,
+                        content,
+                    },
+                ))
+            })
+            .collect::<Result<IndexMap<String, ResponseInfo>>>()?;
+
+        Ok(ApiInfo {
+            service: self.service.to_owned(),
+            path: path.to_owned(),
+            method: method.to_string(),
+            operation_id,
+            tag,
+            parameters,
+            request_body,
+            responses,
+            swagger_url,
+        })
     }
 
-    async fn get_openapi(&self) -> Result<OpenAPI, anyhow::Error> {
-        let json_str = match self.openapi_json_url.starts_with("http") {
-            true => self.json_from_url().await?,
-            false => json_from_file(&self.openapi_json_url)?,
+    /// Loads the spec along with the documents its `$ref`s point into, bundled into one.
+    async fn get_openapi(&self) -> Result<OpenAPI> {
+        let location = &self.openapi_json_url;
+        let root = self.get_document(location).await?;
+        let mut documents = HashMap::new();
+
+        let json = loop {
+            match bundle(location, root.clone(), &documents)? {
+                Bundle::Complete(json) => break json,
+                Bundle::Missing(locations) => {
+                    for location in locations {
+                        let document = self.get_document(&location).await?;
+                        documents.insert(location, document);
+                    }
+                }
+            }
         };
         let openapi: OpenAPI =
-            serde_json::from_str(&json_str).expect("Could not deserialize input");
+            serde_json::from_value(json).map_err(|e| Error::parse(location, e))?;
+
         Ok(openapi)
     }
 
-    async fn json_from_url(&self) -> Result<String, Error> {
-        let response = reqwest::get(&self.openapi_json_url).await?;
+    async fn get_document(&self, location: &str) -> Result<Value> {
+        let json = match location.starts_with("http") {
+            true => self.json_from_url(location).await?,
+            false => json_from_file(location)?,
+        };
+
+        Ok(normalize(json))
+    }
+
+    async fn json_from_url(&self, url: &str) -> Result<Value> {
+        let source = self.config.source.clone().with_env()?;
+        let text = fetch_text(&source, url, &self.openapi_json_url).await?;
 
-        response.text().await
+        parse_document(url, &text)
     }
 
     pub fn render_markdown_table(&self) -> String {
-        let mut markdown = String::new();
-        markdown
-            .push_str("| Path | Method | Parameters | Request Body | Response | Swagger URL |\n");
-        markdown
-            .push_str("| ---- | ------ | ---------- | ------------ | -------- | ----------- |\n");
-        for api_info in &self.api_infos {
-            let path = &api_info.path;
-            let method = &api_info.method;
-            let mut parameters =
-                <Vec<(String, Value)> as AsRef<Vec<(String, Value)>>>::as_ref(&api_info.parameters)
-                    .iter()
-                    .map(|(name, schema_type)| format!(r#""{}": {}"#, name, schema_type))
-                    .collect::<Vec<String>>();
-
-            parameters.sort();
-
-            let parameters = parameters.join(", ");
-            let request_body = api_info
-                .request_body
-                .as_ref()
-                .map(|request_body| request_body.to_string())
-                .unwrap_or_default();
-            let response = api_info
-                .response
-                .as_ref()
-                .map(|response| response.to_string())
-                .unwrap_or_default();
-            let swagger_url = &api_info.swagger_url;
+        render_markdown_table(&self.api_infos, self.config.responses)
+    }
+}
+
+/// Renders operations as a markdown table, led by a `Service` column when they come
+/// from named services.
+pub fn render_markdown_table(api_infos: &[ApiInfo], responses: ResponseMode) -> String {
+    let services = api_infos.iter().any(|api_info| api_info.service.is_some());
+    let mut markdown = String::new();
+    if services {
+        markdown.push_str("| Service ");
+    }
+    markdown.push_str("| Path | Method | Parameters | Request Body | Response | Swagger URL |\n");
+    if services {
+        markdown.push_str("| ------- ");
+    }
+    markdown.push_str("| ---- | ------ | ---------- | ------------ | -------- | ----------- |\n");
+    for api_info in api_infos {
+        if services {
             markdown.push_str(&format!(
-                "| {} | {} | {} | {} | {} | {} |\n",
-                path, method, parameters, request_body, response, swagger_url
+                "| {} ",
+                markdown_cell(api_info.service.as_deref().unwrap_or_default())
             ));
         }
-        markdown
+        let path = &api_info.path;
+        let method = &api_info.method;
+        let mut parameters = api_info
+            .parameters
+            .iter()
+            .map(ParameterInfo::to_string)
+            .collect::<Vec<String>>();
+
+        parameters.sort();
+
+        let parameters = parameters.join(", ");
+        let request_body = render_content(&api_info.request_body);
+        let response = api_info.render_responses(responses);
+        let swagger_url = &api_info.swagger_url;
+        markdown.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            markdown_cell(path),
+            markdown_cell(method),
+            markdown_cell(&parameters),
+            markdown_cell(&request_body),
+            markdown_cell(&response),
+            markdown_cell(swagger_url)
+        ));
     }
+    markdown
+}
+
+/// Escapes text for a GFM table cell, where a bare `|` (as in `"a|b"` enums) would
+/// start a new cell and `<int64>` in `integer<int64>` would be taken for an HTML tag.
+/// The `<br>` line breaks between responses are kept, and newlines (e.g. in multi-line
+/// descriptions), which would end the row, become `<br>` too.
+fn markdown_cell(text: &str) -> String {
+    text.trim()
+        .lines()
+        .flat_map(|line| line.split("<br>"))
+        .map(|line| {
+            line.replace('|', "\\|")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        })
+        .collect::<Vec<String>>()
+        .join("<br>")
 }
 
-fn content_to_value(
+const UNTAGGED: &str = "untagged";
+
+/// Parameters are identified by their name and location.
+fn is_same_parameter(a: &Parameter, b: &Parameter) -> bool {
+    mem::discriminant(a) == mem::discriminant(b)
+        && a.parameter_data_ref().name == b.parameter_data_ref().name
+}
+
+/// Derives an id for an operation without `operationId` the way Swagger UI does, so deep
+/// links still work, e.g. `get` `/pet/{petId}` → `get_pet__petId_`.
+fn fallback_operation_id(method: &str, path: &str) -> String {
+    let path = path
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect::<String>();
+
+    format!("{method}{path}")
+}
+
+/// Characters that would end or split a URL fragment segment.
+const FRAGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`');
+
+/// Links an operation inside the documentation viewer served at `base`. Untagged
+/// operations are listed under `default` by Swagger UI and Scalar. ReDoc only anchors
+/// operations by their `operationId` when they declare one; the others are anchored by
+/// tag, path and method.
+fn deep_link(
+    link_style: LinkStyle,
+    base: &str,
+    tag: Option<&str>,
+    operation_id: Option<&str>,
+    method: &str,
+    path: &str,
+) -> String {
+    let encode = |segment: &str| utf8_percent_encode(segment, FRAGMENT).to_string();
+
+    if let (LinkStyle::Redoc, None) = (link_style, operation_id) {
+        let path = encode(&pointer_token(path));
+
+        return match tag {
+            Some(tag) => format!("{base}#tag/{}/paths/{path}/{method}", encode(tag)),
+            None => format!("{base}#paths/{path}/{method}"),
+        };
+    }
+
+    let operation_id =
+        operation_id.map_or_else(|| fallback_operation_id(method, path), str::to_owned);
+    let tag = tag.unwrap_or("default");
+
+    match link_style {
+        LinkStyle::SwaggerUi => format!("{base}#/{}/{}", encode(tag), encode(&operation_id)),
+        LinkStyle::Redoc => format!("{base}#operation/{}", encode(&operation_id)),
+        LinkStyle::Scalar => {
+            let slug = tag
+                .to_lowercase()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join("-");
+
+            format!(
+                "{base}#tag/{}/{}{path}",
+                encode(&slug),
+                method.to_uppercase()
+            )
+        }
+        LinkStyle::RapiDoc => {
+            let path = path.replace(|c: char| c.is_whitespace() || "#:?&={}".contains(c), "-");
+
+            format!("{base}#{method}-{path}")
+        }
+    }
+}
+
+fn content_to_values(
     content: IndexMap<String, MediaType, RandomState>,
     components: &Components,
-) -> Option<Result<String>> {
-    content.into_iter().next().map(|(_, media_type)| {
-        let ref_or_schema = media_type.schema.to_result("Schema not found")?;
+    config: &Config,
+    direction: Direction,
+) -> Result<Content> {
+    content
+        .into_iter()
+        .map(|(media_type_name, media_type)| {
+            let value =
+                media_type_to_value(&media_type_name, media_type, components, config, direction)?;
+
+            Ok((media_type_name, value.to_string()))
+        })
+        .collect()
+}
+
+/// A media type without a schema (e.g. `image/png: {}`) may hold anything, so it
+/// renders as `"any"`.
+fn media_type_to_value(
+    media_type_name: &str,
+    media_type: MediaType,
+    components: &Components,
+    config: &Config,
+    direction: Direction,
+) -> Result<Value> {
+    let Some(ref_or_schema) = media_type.schema else {
+        return Ok(json!("any"));
+    };
+    let value = Parser::new(config, direction).parse_schema(components, ref_or_schema)?;
 
-        Parser::new()
-            .parse_schema(components, ref_or_schema)
-            .map(|v| v.to_string())
+    Ok(match is_form(media_type_name) {
+        true => annotate_form_parts(value, &media_type.encoding),
+        false => value,
     })
 }
 
-struct Parser {
+fn is_form(media_type: &str) -> bool {
+    media_type.starts_with("multipart/") || media_type == "application/x-www-form-urlencoded"
+}
+
+/// Form bodies are sent part by part: binary properties become `"file"` parts, and a
+/// part with an explicit `encoding` content type is annotated with it, e.g.
+/// `"file (image/png)"`.
+fn annotate_form_parts(value: Value, encoding: &IndexMap<String, Encoding>) -> Value {
+    let Value::Object(map) = value else {
+        return value;
+    };
+
+    let map = map
+        .into_iter()
+        .map(|(key, value)| {
+            let content_type = encoding
+                .get(key.trim_end_matches('?'))
+                .and_then(|encoding| encoding.content_type.as_deref());
+            let annotate = |part: Value| match (part, content_type) {
+                (Value::String(part), Some(content_type)) if part == "string<binary>" => {
+                    json!(format!("file ({content_type})"))
+                }
+                (Value::String(part), None) if part == "string<binary>" => json!("file"),
+                (Value::String(part), Some(content_type)) => {
+                    json!(format!("{part} ({content_type})"))
+                }
+                (part, _) => part,
+            };
+            let value = match value {
+                Value::Array(items) => Value::Array(items.into_iter().map(annotate).collect()),
+                value => annotate(value),
+            };
+
+            (key, value)
+        })
+        .collect();
+
+    Value::Object(map)
+}
+
+/// Which way a schema travels over the wire, used to drop `readOnly`/`writeOnly` properties.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    Request,
+    Response,
+}
+
+struct Parser<'a> {
     stack: Vec<String>,
+    config: &'a Config,
+    direction: Direction,
 }
 
-impl Parser {
-    fn new() -> Self {
-        Self { stack: Vec::new() }
+impl<'a> Parser<'a> {
+    fn new(config: &'a Config, direction: Direction) -> Self {
+        Self {
+            stack: Vec::new(),
+            config,
+            direction,
+        }
     }
 
     fn parse_schema(
@@ -252,70 +727,674 @@ impl Parser {
     ) -> Result<Value> {
         let schema = match ref_or_schema {
             ReferenceOr::Reference { reference } => {
-                let key = reference
-                    .split('/')
-                    .last()
-                    .to_result(format!("Key not found in: {reference}"))?;
+                let (key, schema) = resolve_schema(components, &reference)?;
 
-                if self.stack.contains(&key.to_string()) {
+                if self.stack.contains(&key) {
                     return Ok(json!(key));
                 }
 
-                let schema = components
-                    .schemas
-                    .iter()
-                    .find(|(k, _)| k == &key)
-                    .to_result(format!("key: {key} not found in components"))?
-                    .1
-                    .to_owned()
-                    .into_item()
-                    .to_result(format!("key: {key} is not a schema item"))?;
+                if let Some(subtypes) = self.parse_subtypes(components, &key, &schema)? {
+                    return Ok(subtypes);
+                }
 
-                self.stack.push(key.to_string());
+                self.stack.push(key);
+                let result = self.parse_schema(components, ReferenceOr::Item(schema));
+                self.stack.pop();
 
-                schema
+                return result;
             }
             ReferenceOr::Item(schema) => schema,
         };
+        // An enum listing `null` already says the schema is nullable.
+        let nullable = schema.schema_data.nullable
+            && (self.config.compact_enums || !lists_null(&schema.schema_kind));
+        let discriminator = schema.schema_data.discriminator;
 
         let result = match schema.schema_kind {
             SchemaKind::Type(_type) => match _type {
-                openapiv3::Type::String(_) => json!("string"),
-                openapiv3::Type::Number(_) => json!("number"),
-                openapiv3::Type::Integer(_) => json!("integer"),
+                openapiv3::Type::String(StringType {
+                    format,
+                    pattern,
+                    enumeration,
+                    min_length,
+                    max_length,
+                }) => self.constrain(
+                    self.parse_enum(&format_type_name("string", format), enumeration),
+                    Constraints::default()
+                        .with("minLength", min_length)
+                        .with("maxLength", max_length)
+                        .with("pattern", pattern),
+                ),
+                openapiv3::Type::Number(NumberType {
+                    format,
+                    multiple_of,
+                    exclusive_minimum,
+                    exclusive_maximum,
+                    minimum,
+                    maximum,
+                    enumeration,
+                }) => self.constrain(
+                    self.parse_enum(&format_type_name("number", format), enumeration),
+                    Constraints::default()
+                        .with_bound("minimum", minimum, exclusive_minimum)
+                        .with_bound("maximum", maximum, exclusive_maximum)
+                        .with("multipleOf", multiple_of),
+                ),
+                openapiv3::Type::Integer(IntegerType {
+                    format,
+                    multiple_of,
+                    exclusive_minimum,
+                    exclusive_maximum,
+                    minimum,
+                    maximum,
+                    enumeration,
+                }) => self.constrain(
+                    self.parse_enum(&format_type_name("integer", format), enumeration),
+                    Constraints::default()
+                        .with_bound("minimum", minimum, exclusive_minimum)
+                        .with_bound("maximum", maximum, exclusive_maximum)
+                        .with("multipleOf", multiple_of),
+                ),
                 openapiv3::Type::Boolean(_) => json!("boolean"),
-                openapiv3::Type::Array(ArrayType { items, .. }) => {
-                    let items = items.to_result("Items not found")?;
+                openapiv3::Type::Array(ArrayType {
+                    items,
+                    min_items,
+                    max_items,
+                    unique_items,
+                }) => {
+                    let items = items.ok_or_else(|| Error::UnsupportedSchema {
+                        schema: self.stack.last().cloned().unwrap_or("inline".to_owned()),
+                        reason: "array without items".to_owned(),
+                    })?;
                     let schema_type = self.parse_schema(components, items.unbox())?;
 
-                    json!([schema_type])
+                    self.constrain(
+                        json!([schema_type]),
+                        Constraints::default()
+                            .with("minItems", min_items)
+                            .with("maxItems", max_items)
+                            .with_flag("uniqueItems", unique_items),
+                    )
                 }
-                openapiv3::Type::Object(ObjectType { properties, .. }) => {
-                    let map = properties
+                openapiv3::Type::Object(ObjectType {
+                    properties,
+                    required,
+                    additional_properties,
+                    ..
+                }) => self.parse_object(components, properties, required, additional_properties)?,
+            },
+            SchemaKind::AllOf { all_of } => self.parse_all_of(components, all_of)?,
+            SchemaKind::OneOf { one_of } => {
+                self.parse_union(components, "oneOf", one_of, discriminator.as_ref())?
+            }
+            SchemaKind::AnyOf { any_of } => {
+                self.parse_union(components, "anyOf", any_of, discriminator.as_ref())?
+            }
+            SchemaKind::Not { not } => {
+                json!({ "not": self.parse_schema(components, *not)? })
+            }
+            SchemaKind::Any(any) => self.parse_any(components, any, discriminator.as_ref())?,
+        };
+
+        match (nullable, result) {
+            (true, Value::String(type_name)) => Ok(json!(format!("{type_name} | null"))),
+            (true, result) => Ok(json!({ "oneOf": [result, "null"] })),
+            (false, result) => Ok(result),
+        }
+    }
+
+    /// Whether a property never travels in the parser's direction: `readOnly` properties
+    /// are not sent in requests and `writeOnly` properties are not returned in responses.
+    fn is_excluded(
+        &self,
+        components: &Components,
+        ref_or_schema: &ReferenceOr<Box<Schema>>,
+    ) -> Result<bool> {
+        let schema_data = match ref_or_schema {
+            ReferenceOr::Reference { reference } => {
+                resolve_schema(components, reference)?.1.schema_data
+            }
+            ReferenceOr::Item(schema) => schema.schema_data.clone(),
+        };
+
+        match self.direction {
+            Direction::Request => Ok(schema_data.read_only),
+            Direction::Response => Ok(schema_data.write_only),
+        }
+    }
+
+    /// Renders properties keyed by name, plus a `"<string>"` key describing the values of
+    /// map-like objects (`additionalProperties`). Free-form objects map to `"any"`.
+    fn parse_object(
+        &mut self,
+        components: &Components,
+        properties: IndexMap<String, ReferenceOr<Box<Schema>>>,
+        required: Vec<String>,
+        additional_properties: Option<AdditionalProperties>,
+    ) -> Result<Value> {
+        let mut map = Map::new();
+
+        for (s, ref_or_schema) in properties {
+            if self.is_excluded(components, &ref_or_schema)? {
+                continue;
+            }
+
+            let key = match required.contains(&s) {
+                true => s,
+                false => format!("{s}?"),
+            };
+
+            map.insert(key, self.parse_schema(components, ref_or_schema.unbox())?);
+        }
+
+        let additional_properties = match additional_properties {
+            Some(AdditionalProperties::Schema(ref_or_schema)) => {
+                Some(self.parse_schema(components, *ref_or_schema)?)
+            }
+            Some(AdditionalProperties::Any(true)) => Some(json!("any")),
+            Some(AdditionalProperties::Any(false)) | None => None,
+        };
+
+        if let Some(value) = additional_properties {
+            map.insert("<string>".to_owned(), value);
+        }
+
+        Ok(Value::Object(map))
+    }
+
+    /// Annotates a leaf type with its validation keywords, e.g.
+    /// `"string (minLength=1, pattern=^[a-z]+$)"`. Arrays become
+    /// `{"items": [...], "minItems": 1}`. Only applies in `constraints` mode.
+    fn constrain(&self, value: Value, Constraints(constraints): Constraints) -> Value {
+        if !self.config.constraints || constraints.is_empty() {
+            return value;
+        }
+
+        match value {
+            Value::String(type_name) => {
+                let constraints = constraints
+                    .into_iter()
+                    .map(|(keyword, value)| match value {
+                        Value::Bool(true) => keyword.to_owned(),
+                        Value::String(value) => format!("{keyword}={value}"),
+                        value => format!("{keyword}={value}"),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                json!(format!("{type_name} ({constraints})"))
+            }
+            items @ Value::Array(_) => {
+                let mut map = Map::new();
+                map.insert("items".to_owned(), items);
+                map.extend(
+                    constraints
                         .into_iter()
-                        .map(|(s, ref_or_schema)| {
-                            self.parse_schema(components, ref_or_schema.unbox())
-                                .map(|v| (s, v))
-                        })
-                        .collect::<Result<Map<String, Value>>>()?;
+                        .map(|(keyword, value)| (keyword.to_owned(), value)),
+                );
+
+                Value::Object(map)
+            }
+            value => value,
+        }
+    }
+
+    /// Renders enumerated members as `"a|b|c"`, or just the type name when the schema
+    /// is not an enum or `compact_enums` is set.
+    fn parse_enum<T: ToString>(&self, type_name: &str, enumeration: Vec<Option<T>>) -> Value {
+        if enumeration.is_empty() || self.config.compact_enums {
+            return json!(type_name);
+        }
+
+        let members = enumeration
+            .into_iter()
+            .map(|member| member.map_or("null".to_owned(), |member| member.to_string()))
+            .collect::<Vec<String>>()
+            .join("|");
+
+        json!(members)
+    }
+
+    fn parse_schemas(
+        &mut self,
+        components: &Components,
+        ref_or_schemas: Vec<ReferenceOr<Schema>>,
+    ) -> Result<Vec<Value>> {
+        ref_or_schemas
+            .into_iter()
+            .map(|ref_or_schema| self.parse_schema(components, ref_or_schema))
+            .collect()
+    }
 
-                    serde_json::Value::Object(map)
+    /// Renders `oneOf`/`anyOf` as an explicit `{"oneOf": [...]}` marker. With a
+    /// discriminator, each alternative is keyed by its discriminator value instead, e.g.
+    /// `{"petType=cat": {...}, "petType=dog": {...}}`; that needs every alternative to
+    /// be a `$ref`, since inline alternatives have no name to fall back on.
+    fn parse_union(
+        &mut self,
+        components: &Components,
+        marker: &str,
+        members: Vec<ReferenceOr<Schema>>,
+        discriminator: Option<&Discriminator>,
+    ) -> Result<Value> {
+        let references = members
+            .iter()
+            .filter_map(|member| match member {
+                ReferenceOr::Reference { reference } => Some(reference.to_owned()),
+                ReferenceOr::Item(_) => None,
+            })
+            .collect::<Vec<String>>();
+
+        let discriminator = match discriminator {
+            Some(discriminator) if references.len() == members.len() => discriminator,
+            _ => return Ok(json!({ marker: self.parse_schemas(components, members)? })),
+        };
+
+        let mut map = Map::new();
+
+        for reference in references {
+            let value = discriminator
+                .mapping
+                .iter()
+                .find(|(_, target)| mapping_reference(target) == reference)
+                .map(|(value, _)| value.to_owned());
+            let value = match value {
+                Some(value) => value,
+                None => component_name(&reference, "schemas")?,
+            };
+            let subtype = self.parse_schema(components, ReferenceOr::Reference { reference })?;
+
+            map.insert(format!("{}={value}", discriminator.property_name), subtype);
+        }
+
+        Ok(Value::Object(map))
+    }
+
+    /// A base schema whose discriminator maps to subtypes (the `allOf` inheritance
+    /// pattern) is rendered as every subtype keyed by its discriminator value. Subtypes
+    /// include the base through `allOf`, so while one of them is being rendered the base
+    /// is rendered plainly.
+    fn parse_subtypes(
+        &mut self,
+        components: &Components,
+        key: &str,
+        schema: &Schema,
+    ) -> Result<Option<Value>> {
+        let discriminator = match &schema.schema_data.discriminator {
+            Some(discriminator)
+                if !discriminator.mapping.is_empty()
+                    && !matches!(
+                        schema.schema_kind,
+                        SchemaKind::OneOf { .. } | SchemaKind::AnyOf { .. }
+                    ) =>
+            {
+                discriminator
+            }
+            _ => return Ok(None),
+        };
+
+        let subtypes = discriminator
+            .mapping
+            .iter()
+            .map(|(value, target)| {
+                let reference = mapping_reference(target);
+                let subtype_key = component_name(&reference, "schemas")?;
+
+                Ok((value, reference, subtype_key))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if subtypes
+            .iter()
+            .any(|(_, _, subtype_key)| self.stack.contains(subtype_key))
+        {
+            return Ok(None);
+        }
+
+        let mut map = Map::new();
+
+        for (value, reference, subtype_key) in subtypes {
+            let subtype = match subtype_key == key {
+                true => {
+                    self.stack.push(subtype_key);
+                    let subtype = self.parse_schema(components, ReferenceOr::Item(schema.clone()));
+                    self.stack.pop();
+
+                    subtype?
                 }
-            },
-            _ => todo!(),
+                false => self.parse_schema(components, ReferenceOr::Reference { reference })?,
+            };
+
+            map.insert(format!("{}={value}", discriminator.property_name), subtype);
+        }
+
+        Ok(Some(Value::Object(map)))
+    }
+
+    fn parse_all_of(
+        &mut self,
+        components: &Components,
+        all_of: Vec<ReferenceOr<Schema>>,
+    ) -> Result<Value> {
+        let (values, required) = self.parse_all_of_members(components, all_of)?;
+
+        match values.is_empty() {
+            true => Ok(json!("any")),
+            false => Ok(require(merge_all_of(values), &required)),
+        }
+    }
+
+    /// Renders `allOf` members, leaving out the ones that only carry annotations or
+    /// constraints (e.g. `{"required": ["name"]}` next to a `$ref`), along with the
+    /// properties the inline members require.
+    fn parse_all_of_members(
+        &mut self,
+        components: &Components,
+        all_of: Vec<ReferenceOr<Schema>>,
+    ) -> Result<(Vec<Value>, Vec<String>)> {
+        let mut values = Vec::new();
+        let mut required = Vec::new();
+
+        for member in all_of {
+            if let ReferenceOr::Item(schema) = &member {
+                match &schema.schema_kind {
+                    SchemaKind::Type(openapiv3::Type::Object(object)) => {
+                        required.extend(object.required.iter().cloned());
+                    }
+                    SchemaKind::Any(any) => {
+                        required.extend(any.required.iter().cloned());
+
+                        if is_shapeless(any) {
+                            continue;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            values.push(self.parse_schema(components, member)?);
+        }
+
+        Ok((values, required))
+    }
+
+    /// Schemas without a `type` (or mixing keywords of several kinds) land here. The
+    /// shape is inferred from the keywords present, falling back to `"any"`.
+    fn parse_any(
+        &mut self,
+        components: &Components,
+        any: AnySchema,
+        discriminator: Option<&Discriminator>,
+    ) -> Result<Value> {
+        let AnySchema {
+            typ,
+            pattern,
+            multiple_of,
+            exclusive_minimum,
+            exclusive_maximum,
+            minimum,
+            maximum,
+            properties,
+            required,
+            additional_properties,
+            items,
+            min_items,
+            max_items,
+            unique_items,
+            enumeration,
+            format,
+            min_length,
+            max_length,
+            one_of,
+            all_of,
+            any_of,
+            not,
+            ..
+        } = any;
+        let mut values = Vec::new();
+
+        if typ.as_deref() == Some("object")
+            || !properties.is_empty()
+            || additional_properties.is_some()
+        {
+            values.push(self.parse_object(
+                components,
+                properties,
+                required,
+                additional_properties,
+            )?);
+        } else if typ.as_deref() == Some("array") || items.is_some() {
+            let items = match items {
+                Some(items) => self.parse_schema(components, items.unbox())?,
+                None => json!("any"),
+            };
+
+            values.push(
+                self.constrain(
+                    json!([items]),
+                    Constraints::default()
+                        .with("minItems", min_items)
+                        .with("maxItems", max_items)
+                        .with_flag("uniqueItems", unique_items.unwrap_or_default()),
+                ),
+            );
+        } else if typ.is_some() || !enumeration.is_empty() {
+            let typ = typ.unwrap_or("any".to_owned());
+            let type_name = match format {
+                Some(format) => format!("{typ}<{format}>"),
+                None => typ,
+            };
+            let enumeration = enumeration
+                .into_iter()
+                .map(|member| match member {
+                    Value::Null => None,
+                    Value::String(member) => Some(member),
+                    member => Some(member.to_string()),
+                })
+                .collect::<Vec<Option<String>>>();
+
+            values.push(
+                self.constrain(
+                    self.parse_enum(&type_name, enumeration),
+                    Constraints::default()
+                        .with("minLength", min_length)
+                        .with("maxLength", max_length)
+                        .with("pattern", pattern)
+                        .with_bound("minimum", minimum, exclusive_minimum.unwrap_or_default())
+                        .with_bound("maximum", maximum, exclusive_maximum.unwrap_or_default())
+                        .with("multipleOf", multiple_of),
+                ),
+            );
+        }
+
+        let (members, all_of_required) = self.parse_all_of_members(components, all_of)?;
+        values.extend(members);
+
+        for (marker, members) in [("oneOf", one_of), ("anyOf", any_of)] {
+            if !members.is_empty() {
+                values.push(self.parse_union(components, marker, members, discriminator)?);
+            }
+        }
+
+        if let Some(not) = not {
+            values.push(json!({ "not": self.parse_schema(components, *not)? }));
+        }
+
+        match values.is_empty() {
+            true => Ok(json!("any")),
+            false => Ok(require(merge_all_of(values), &all_of_required)),
+        }
+    }
+}
+
+/// Validation keywords collected for the `constraints` rendering mode, in render order.
+#[derive(Default)]
+struct Constraints(Vec<(&'static str, Value)>);
+
+impl Constraints {
+    fn with(mut self, keyword: &'static str, value: Option<impl Into<Value>>) -> Self {
+        if let Some(value) = value {
+            self.0.push((keyword, value.into()));
+        }
+
+        self
+    }
+
+    /// Adds `minimum`/`maximum`, switching to `exclusiveMinimum`/`exclusiveMaximum`
+    /// when the bound is exclusive.
+    fn with_bound(
+        self,
+        keyword: &'static str,
+        value: Option<impl Into<Value>>,
+        exclusive: bool,
+    ) -> Self {
+        let keyword = match (keyword, exclusive) {
+            ("minimum", true) => "exclusiveMinimum",
+            ("maximum", true) => "exclusiveMaximum",
+            (keyword, _) => keyword,
         };
 
-        Ok(result)
+        self.with(keyword, value)
     }
+
+    fn with_flag(self, keyword: &'static str, flag: bool) -> Self {
+        self.with(keyword, flag.then_some(true))
+    }
+}
+
+/// Discriminator mappings may name a schema instead of giving a full `$ref`.
+fn mapping_reference(target: &str) -> String {
+    match target.contains('/') {
+        true => target.to_owned(),
+        false => format!("#/components/schemas/{}", pointer_token(target)),
+    }
+}
+
+/// Looks up the schema a `$ref` points at, along with the key used to detect recursion.
+fn resolve_schema(components: &Components, reference: &str) -> Result<(String, Schema)> {
+    let key = component_name(reference, "schemas")?;
+    let schema = ReferenceOr::<Schema>::ref_(reference).resolve(components)?;
+
+    Ok((key, schema))
 }
 
-fn json_from_file(path: &str) -> Result<String> {
-    // let file = std::fs::File::open("data/api-docs.json")?;
-    let file = std::fs::File::open(path)?;
-    let reader = std::io::BufReader::new(file);
-    let json: Value = serde_json::from_reader(reader)?;
+/// Merges `allOf` members into a single object shape. A nullable member is merged as
+/// its object. Members that are not property maps (e.g. a recursive reference, a
+/// `oneOf` marker or discriminated subtypes) cannot be merged, so they are kept as an
+/// explicit `{"allOf": [...]}` marker instead.
+fn merge_all_of(mut values: Vec<Value>) -> Value {
+    if values.len() == 1 {
+        return values.remove(0);
+    }
+
+    if !values
+        .iter()
+        .all(|value| non_null(value).as_object().is_some_and(is_property_map))
+    {
+        return json!({ "allOf": values });
+    }
+
+    let map = values
+        .iter()
+        .filter_map(|value| non_null(value).as_object())
+        .flatten()
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect::<Map<String, Value>>();
 
-    Ok(json.to_string())
+    Value::Object(map)
+}
+
+/// The rendering of a nullable object without its `{"oneOf": [..., "null"]}` wrapper.
+fn non_null(value: &Value) -> &Value {
+    match value
+        .get("oneOf")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        Some([value, Value::String(null)]) if value.is_object() && null == "null" => value,
+        _ => value,
+    }
+}
+
+/// Whether a rendered object lists properties, rather than being a marker such as
+/// `{"oneOf": [...]}` or `{"not": ...}`, a constrained array or discriminated subtypes
+/// keyed by `property=value`.
+fn is_property_map(map: &Map<String, Value>) -> bool {
+    let keys = map.keys().map(String::as_str).collect::<Vec<&str>>();
+    let is_marker = matches!(keys.as_slice(), ["allOf" | "oneOf" | "anyOf" | "not"]);
+    let is_array = map.get("items").is_some_and(Value::is_array)
+        && keys
+            .iter()
+            .all(|key| ["items", "minItems", "maxItems", "uniqueItems"].contains(key));
+    let is_subtypes = keys.iter().all(|key| key.contains('='));
+
+    map.is_empty() || !(is_marker || is_array || is_subtypes)
+}
+
+/// Whether the `enum` of a schema has a `null` member.
+fn lists_null(schema_kind: &SchemaKind) -> bool {
+    match schema_kind {
+        SchemaKind::Type(openapiv3::Type::String(string)) => string.enumeration.contains(&None),
+        SchemaKind::Type(openapiv3::Type::Number(number)) => number.enumeration.contains(&None),
+        SchemaKind::Type(openapiv3::Type::Integer(integer)) => integer.enumeration.contains(&None),
+        SchemaKind::Type(openapiv3::Type::Boolean(boolean)) => boolean.enumeration.contains(&None),
+        SchemaKind::Any(any) => any.enumeration.contains(&Value::Null),
+        _ => false,
+    }
+}
+
+/// Whether a schema without a `type` describes no shape at all, only annotations or
+/// constraints.
+fn is_shapeless(any: &AnySchema) -> bool {
+    any.typ.is_none()
+        && any.properties.is_empty()
+        && any.additional_properties.is_none()
+        && any.items.is_none()
+        && any.enumeration.is_empty()
+        && any.one_of.is_empty()
+        && any.all_of.is_empty()
+        && any.any_of.is_empty()
+        && any.not.is_none()
+}
+
+/// Marks the `required` properties of a merged object as required, e.g. `name?` →
+/// `name`.
+fn require(value: Value, required: &[String]) -> Value {
+    let Value::Object(mut map) = value else {
+        return value;
+    };
+
+    for name in required {
+        if let Some(property) = map.remove(&format!("{name}?")) {
+            map.insert(name.to_owned(), property);
+        }
+    }
+
+    Value::Object(map)
+}
+
+/// Appends the schema format to its type name, e.g. `string<date-time>` or `integer<int64>`.
+fn format_type_name<T: Serialize>(type_name: &str, format: VariantOrUnknownOrEmpty<T>) -> String {
+    let format = match format {
+        VariantOrUnknownOrEmpty::Item(format) => serde_json::to_value(format)
+            .ok()
+            .and_then(|format| format.as_str().map(str::to_owned)),
+        VariantOrUnknownOrEmpty::Unknown(format) => Some(format),
+        VariantOrUnknownOrEmpty::Empty => None,
+    };
+
+    match format {
+        Some(format) => format!("{type_name}<{format}>"),
+        None => type_name.to_owned(),
+    }
+}
+
+/// Reads a JSON or YAML spec. Object keys come back sorted, so paths are listed in
+/// the same order whichever format the spec is written in.
+fn json_from_file(path: &str) -> Result<Value> {
+    let text = std::fs::read_to_string(path).map_err(|e| Error::fetch(path, e))?;
+
+    parse_document(path, &text)
 }
 
 #[cfg(test)]
@@ -324,9 +1403,13 @@ mod tests {
     use dotenv::from_filename;
     use openapiv3::{ArrayType, Schema, SchemaData, SchemaKind, Type};
     use std::env;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
 
     #[tokio::test]
-    async fn content_to_value_test() {
+    async fn content_to_values_test() {
         from_filename(".env.test").ok();
         let swagger_ui_url = &env::var("SWAGGER_UI_URL").expect("SWAGGER_UI_URL must be set");
         let openapi_json_url = "data/pet.json";
@@ -370,11 +1453,16 @@ mod tests {
 
             content
         };
-        let actual = content_to_value(content, &openapi.components.unwrap())
-            .unwrap()
-            .unwrap()
-            .to_string();
-        let expected = r#"[{"email":"string","firstName":"string","id":"integer","lastName":"string","password":"string","phone":"string","userStatus":"integer","username":"string"}]"#;
+        let actual = content_to_values(
+            content,
+            &openapi.components.unwrap(),
+            &Config::default(),
+            Direction::Response,
+        )
+        .unwrap()
+        .swap_remove("application/json")
+        .unwrap();
+        let expected = r#"[{"email?":"string","firstName?":"string","id?":"integer<int64>","lastName?":"string","password?":"string","phone?":"string","userStatus?":"integer<int32>","username?":"string"}]"#;
         assert_eq!(actual, expected);
     }
 
@@ -385,14 +1473,936 @@ mod tests {
         let openapi_json_url = "data/pet.json";
 
         let humbler = Humbler::new(swagger_ui_url.to_string(), openapi_json_url.to_string());
-        let api_infos = humbler.get_api_infos().await.unwrap();
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
         let post_pet = api_infos
             .into_iter()
             .find(|api_info| api_info.path == "/pet" && api_info.method == "post")
             .unwrap();
 
-        let actual = post_pet.request_body.unwrap();
-        let expected = r#"{"category":{"id":"integer","name":"string"},"children":["Pet"],"id":"integer","name":"string","photoUrls":["string"],"status":"string","tags":[{"id":"integer","name":"string"}]}"#;
+        let actual = render_content(&post_pet.request_body);
+        let expected = r#"{"category?":{"id?":"integer<int64>","name?":"string"},"children?":["Pet"],"id?":"integer<int64>","name":"string","photoUrls":["string"],"status?":"available|pending|sold","tags?":[{"id?":"integer<int64>","name?":"string"}]}"#;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn parse_composed_schemas() {
+        let components: Components = serde_json::from_value(json!({
+            "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": { "name": { "type": "string" } }
+                },
+                "Cat": {
+                    "allOf": [
+                        { "$ref": "#/components/schemas/Pet" },
+                        { "type": "object", "properties": { "lives": { "type": "integer" } } }
+                    ]
+                },
+                "Dog": {
+                    "allOf": [
+                        { "$ref": "#/components/schemas/Pet" },
+                        { "type": "object", "properties": { "bark": { "type": "boolean" } } }
+                    ]
+                },
+                "Animal": {
+                    "oneOf": [
+                        { "$ref": "#/components/schemas/Cat" },
+                        { "$ref": "#/components/schemas/Dog" }
+                    ]
+                },
+                "Id": {
+                    "anyOf": [{ "type": "string" }, { "type": "integer" }]
+                },
+                "NotString": {
+                    "not": { "type": "string" }
+                },
+                "Named": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string" },
+                        "nickname": { "type": "string" }
+                    }
+                },
+                "RequiredName": {
+                    "allOf": [
+                        { "$ref": "#/components/schemas/Named" },
+                        { "required": ["name"] },
+                        { "description": "A named thing" }
+                    ]
+                },
+                "Base": {
+                    "type": "object",
+                    "nullable": true,
+                    "properties": { "id": { "type": "integer" } }
+                },
+                "Shape": {
+                    "oneOf": [
+                        { "type": "object", "properties": { "r": { "type": "number" } } },
+                        { "type": "object", "properties": { "w": { "type": "number" } } }
+                    ]
+                },
+                "Nullable": {
+                    "allOf": [
+                        { "$ref": "#/components/schemas/Base" },
+                        { "type": "object", "properties": { "lives": { "type": "integer" } } }
+                    ]
+                },
+                "Mixed": {
+                    "allOf": [
+                        { "$ref": "#/components/schemas/Base" },
+                        { "$ref": "#/components/schemas/Shape" },
+                        { "type": "object", "properties": { "lives": { "type": "integer" } } }
+                    ]
+                }
+            }
+        }))
+        .unwrap();
+        let config = Config::default();
+        let parse = |key: &str| {
+            Parser::new(&config, Direction::Request)
+                .parse_schema(
+                    &components,
+                    ReferenceOr::Reference {
+                        reference: format!("#/components/schemas/{key}"),
+                    },
+                )
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(parse("Cat"), r#"{"lives?":"integer","name":"string"}"#);
+        assert_eq!(
+            parse("Animal"),
+            r#"{"oneOf":[{"lives?":"integer","name":"string"},{"bark?":"boolean","name":"string"}]}"#
+        );
+        assert_eq!(parse("Id"), r#"{"anyOf":["string","integer"]}"#);
+        assert_eq!(parse("NotString"), r#"{"not":"string"}"#);
+        assert_eq!(
+            parse("RequiredName"),
+            r#"{"name":"string","nickname?":"string"}"#
+        );
+        assert_eq!(parse("Nullable"), r#"{"id?":"integer","lives?":"integer"}"#);
+        assert_eq!(
+            parse("Mixed"),
+            r#"{"allOf":[{"oneOf":[{"id?":"integer"},"null"]},{"oneOf":[{"r?":"number"},{"w?":"number"}]},{"lives?":"integer"}]}"#
+        );
+    }
+
+    #[test]
+    fn parse_enum_schema() {
+        let components = Components::default();
+        let schema: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {
+                "status": { "type": "string", "enum": ["available", "pending", "sold"] },
+                "level": { "type": "integer", "enum": [1, 2, 3] },
+                "ratio": { "type": "number", "enum": [0.5, 1.5] },
+                "size": { "type": "string", "enum": ["s", "m", null], "nullable": true }
+            }
+        }))
+        .unwrap();
+
+        let actual = Parser::new(&Config::default(), Direction::Request)
+            .parse_schema(&components, ReferenceOr::Item(schema.clone()))
+            .unwrap()
+            .to_string();
+        let expected = r#"{"level?":"1|2|3","ratio?":"0.5|1.5","size?":"s|m|null","status?":"available|pending|sold"}"#;
+        assert_eq!(actual, expected);
+
+        let config = Config {
+            compact_enums: true,
+            ..Default::default()
+        };
+        let actual = Parser::new(&config, Direction::Request)
+            .parse_schema(&components, ReferenceOr::Item(schema))
+            .unwrap()
+            .to_string();
+        let expected =
+            r#"{"level?":"integer","ratio?":"number","size?":"string | null","status?":"string"}"#;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn parse_formatted_schema() {
+        let schema: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "required": ["id"],
+            "properties": {
+                "id": { "type": "string", "format": "uuid" },
+                "createdAt": { "type": "string", "format": "date-time" },
+                "avatar": { "type": "string", "format": "binary" },
+                "count": { "type": "integer", "format": "int64" },
+                "price": { "type": "number", "format": "double" }
+            }
+        }))
+        .unwrap();
+
+        let actual = Parser::new(&Config::default(), Direction::Request)
+            .parse_schema(&Components::default(), ReferenceOr::Item(schema))
+            .unwrap()
+            .to_string();
+        let expected = r#"{"avatar?":"string<binary>","count?":"integer<int64>","createdAt?":"string<date-time>","id":"string<uuid>","price?":"number<double>"}"#;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn parse_map_schema() {
+        let components: Components = serde_json::from_value(json!({
+            "schemas": {
+                "Tag": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": { "name": { "type": "string" } }
+                }
+            }
+        }))
+        .unwrap();
+        let schema: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "required": ["tags", "metadata", "empty"],
+            "properties": {
+                "tags": {
+                    "type": "object",
+                    "additionalProperties": { "$ref": "#/components/schemas/Tag" }
+                },
+                "metadata": { "type": "object", "additionalProperties": true },
+                "empty": { "type": "object" }
+            }
+        }))
+        .unwrap();
+
+        let actual = Parser::new(&Config::default(), Direction::Request)
+            .parse_schema(&components, ReferenceOr::Item(schema))
+            .unwrap()
+            .to_string();
+        let expected =
+            r#"{"empty":{},"metadata":{"<string>":"any"},"tags":{"<string>":{"name":"string"}}}"#;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn parse_untyped_schema() {
+        let schema: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "required": ["payload", "owner"],
+            "properties": {
+                "payload": {},
+                "owner": {
+                    "properties": { "name": { "type": "string" } }
+                },
+                "values": { "items": { "type": "integer" } },
+                "kind": { "enum": ["a", "b"] },
+                "extended": {
+                    "type": "object",
+                    "properties": { "id": { "type": "integer" } },
+                    "allOf": [{ "type": "object", "properties": { "note": { "type": "string" } } }]
+                }
+            }
+        }))
+        .unwrap();
+
+        let actual = Parser::new(&Config::default(), Direction::Request)
+            .parse_schema(&Components::default(), ReferenceOr::Item(schema))
+            .unwrap()
+            .to_string();
+        let expected = r#"{"extended?":{"id?":"integer","note?":"string"},"kind?":"a|b","owner":{"name?":"string"},"payload":"any","values?":["integer"]}"#;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn parse_schema_per_direction() {
+        let components: Components = serde_json::from_value(json!({
+            "schemas": {
+                "Id": { "type": "integer", "readOnly": true }
+            }
+        }))
+        .unwrap();
+        let schema: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "required": ["id", "name", "password"],
+            "properties": {
+                "id": { "$ref": "#/components/schemas/Id" },
+                "name": { "type": "string", "nullable": true },
+                "password": { "type": "string", "writeOnly": true },
+                "tags": { "type": "array", "items": { "type": "string" }, "nullable": true }
+            }
+        }))
+        .unwrap();
+        let config = Config::default();
+
+        let actual = Parser::new(&config, Direction::Request)
+            .parse_schema(&components, ReferenceOr::Item(schema.clone()))
+            .unwrap()
+            .to_string();
+        let expected =
+            r#"{"name":"string | null","password":"string","tags?":{"oneOf":[["string"],"null"]}}"#;
+        assert_eq!(actual, expected);
+
+        let actual = Parser::new(&config, Direction::Response)
+            .parse_schema(&components, ReferenceOr::Item(schema))
+            .unwrap()
+            .to_string();
+        let expected =
+            r#"{"id":"integer","name":"string | null","tags?":{"oneOf":[["string"],"null"]}}"#;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn parse_constrained_schema() {
+        let schema: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "required": ["name", "age", "tags"],
+            "properties": {
+                "name": { "type": "string", "minLength": 1, "maxLength": 50, "pattern": "^[a-z]+$" },
+                "age": { "type": "integer", "minimum": 0, "maximum": 150, "exclusiveMaximum": true },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "minItems": 1,
+                    "uniqueItems": true
+                }
+            }
+        }))
+        .unwrap();
+        let config = Config {
+            constraints: true,
+            ..Default::default()
+        };
+
+        let actual = Parser::new(&config, Direction::Request)
+            .parse_schema(&Components::default(), ReferenceOr::Item(schema.clone()))
+            .unwrap()
+            .to_string();
+        let expected = r#"{"age":"integer (minimum=0, exclusiveMaximum=150)","name":"string (minLength=1, maxLength=50, pattern=^[a-z]+$)","tags":{"items":["string"],"minItems":1,"uniqueItems":true}}"#;
+        assert_eq!(actual, expected);
+
+        let actual = Parser::new(&Config::default(), Direction::Request)
+            .parse_schema(&Components::default(), ReferenceOr::Item(schema))
+            .unwrap()
+            .to_string();
+        let expected = r#"{"age":"integer","name":"string","tags":["string"]}"#;
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn parse_discriminated_schemas() {
+        let components: Components = serde_json::from_value(json!({
+            "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "required": ["petType"],
+                    "properties": { "petType": { "type": "string" } },
+                    "discriminator": {
+                        "propertyName": "petType",
+                        "mapping": {
+                            "cat": "#/components/schemas/Cat",
+                            "dog": "Dog"
+                        }
+                    }
+                },
+                "Cat": {
+                    "allOf": [
+                        { "$ref": "#/components/schemas/Pet" },
+                        { "type": "object", "properties": { "lives": { "type": "integer" } } }
+                    ]
+                },
+                "Dog": {
+                    "allOf": [
+                        { "$ref": "#/components/schemas/Pet" },
+                        { "type": "object", "properties": { "bark": { "type": "boolean" } } }
+                    ]
+                },
+                "Animal": {
+                    "oneOf": [
+                        { "$ref": "#/components/schemas/Cat" },
+                        { "$ref": "#/components/schemas/Dog" }
+                    ],
+                    "discriminator": { "propertyName": "petType" }
+                }
+            }
+        }))
+        .unwrap();
+        let config = Config::default();
+        let parse = |key: &str| {
+            Parser::new(&config, Direction::Request)
+                .parse_schema(
+                    &components,
+                    ReferenceOr::Reference {
+                        reference: format!("#/components/schemas/{key}"),
+                    },
+                )
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            parse("Pet"),
+            r#"{"petType=cat":{"lives?":"integer","petType":"string"},"petType=dog":{"bark?":"boolean","petType":"string"}}"#
+        );
+        assert_eq!(parse("Cat"), r#"{"lives?":"integer","petType":"string"}"#);
+        assert_eq!(
+            parse("Animal"),
+            r#"{"petType=Cat":{"lives?":"integer","petType":"string"},"petType=Dog":{"bark?":"boolean","petType":"string"}}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn collect_all_responses() {
+        let humbler = Humbler::new(String::new(), "data/pet.json".to_owned());
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+        let find = |method: &str, path: &str| {
+            api_infos
+                .iter()
+                .find(|api_info| api_info.method == method && api_info.path == path)
+                .unwrap()
+        };
+        let get_order = find("get", "/store/order/{orderId}");
+
+        let statuses = get_order.responses.keys().collect::<Vec<_>>();
+        assert_eq!(statuses, ["200", "400", "404"]);
+
+        let order = r#"{"complete?":"boolean","id?":"integer<int64>","petId?":"integer<int64>","quantity?":"integer<int32>","shipDate?":"string<date-time>","status?":"placed|approved|delivered"}"#;
+        assert_eq!(get_order.render_responses(ResponseMode::Success), order);
+        assert_eq!(
+            get_order.render_responses(ResponseMode::All),
+            format!("200 (successful operation): {order}<br>400 (Invalid ID supplied)<br>404 (Order not found)")
+        );
+
+        let create_user = find("post", "/user");
+        let user = r#"{"email?":"string","firstName?":"string","id?":"integer<int64>","lastName?":"string","password?":"string","phone?":"string","userStatus?":"integer<int32>","username?":"string"}"#;
+        assert_eq!(
+            create_user.responses.keys().collect::<Vec<_>>(),
+            ["default"]
+        );
+        assert_eq!(create_user.render_responses(ResponseMode::Success), user);
+    }
+
+    #[test]
+    fn content_to_values_per_media_type() {
+        let content: IndexMap<String, MediaType> = serde_json::from_value(json!({
+            "application/json": {
+                "schema": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": { "name": { "type": "string" } }
+                }
+            },
+            "multipart/form-data": {
+                "schema": {
+                    "type": "object",
+                    "required": ["name", "avatar"],
+                    "properties": {
+                        "name": { "type": "string" },
+                        "avatar": { "type": "string", "format": "binary" },
+                        "attachments": {
+                            "type": "array",
+                            "items": { "type": "string", "format": "binary" }
+                        }
+                    }
+                },
+                "encoding": {
+                    "avatar": { "contentType": "image/png" }
+                }
+            },
+            "image/png": {}
+        }))
+        .unwrap();
+
+        let content = content_to_values(
+            content,
+            &Components::default(),
+            &Config::default(),
+            Direction::Request,
+        )
+        .unwrap();
+        assert_eq!(
+            content.get("multipart/form-data").unwrap(),
+            r#"{"attachments?":["file"],"avatar":"file (image/png)","name":"string"}"#
+        );
+        assert_eq!(
+            render_content(&content),
+            r#"application/json: {"name":"string"}<br>image/png: "any"<br>multipart/form-data: {"attachments?":["file"],"avatar":"file (image/png)","name":"string"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn collect_header_and_cookie_parameters() {
+        let humbler = Humbler::new(String::new(), "data/gateway.json".to_owned());
+        let parameters = |humbler: Humbler| async move {
+            humbler
+                .get_api_infos()
+                .await
+                .unwrap()
+                .0
+                .into_iter()
+                .find(|api_info| api_info.method == "get" && api_info.path.starts_with("/tenants"))
+                .unwrap()
+                .parameters
+                .iter()
+                .map(ParameterInfo::to_string)
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            parameters(humbler.clone()).await,
+            [
+                r#""tenantId": "string""#,
+                r#"header "X-Tenant-Id": "string<uuid>""#,
+                r#"header "Accept?": "string""#,
+                r#"cookie "SESSION": "string""#,
+            ]
+        );
+
+        let config = Config {
+            hide_well_known_headers: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            parameters(humbler.with_config(config)).await,
+            [
+                r#""tenantId": "string""#,
+                r#"header "X-Tenant-Id": "string<uuid>""#,
+                r#"cookie "SESSION": "string""#,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn resolve_referenced_components() {
+        let humbler = Humbler::new(String::new(), "data/gateway.json".to_owned());
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+
+        let find = |method: &str, path: &str| {
+            api_infos
+                .iter()
+                .find(|api_info| api_info.method == method && api_info.path == path)
+                .unwrap()
+        };
+
+        let create_order = find("post", "/tenants/{tenantId}/orders");
+        assert_eq!(
+            create_order.parameters[0].to_string(),
+            r#""tenantId": "string""#
+        );
+        assert_eq!(
+            render_content(&create_order.request_body),
+            r#"{"id":"string<uuid>","quantity?":"integer"}"#
+        );
+        assert_eq!(
+            create_order.render_responses(ResponseMode::All),
+            r#"201 (Created): {"id":"string<uuid>","quantity?":"integer"}<br>400 (Error): {"code":"integer","message":"string"}"#
+        );
+
+        let search_orders = find("get", "/orders");
+        let parameters = search_orders
+            .parameters
+            .iter()
+            .map(ParameterInfo::to_string)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            parameters,
+            [r#""page?": "integer""#, r#""size?": "integer""#]
+        );
+        assert_eq!(
+            search_orders.responses["default"].content["application/json"],
+            r#"{"code":"integer","message":"string"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn collect_content_parameters() {
+        let humbler = Humbler::new(String::new(), "data/gateway.json".to_owned());
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+        let export_orders = api_infos
+            .iter()
+            .find(|api_info| api_info.path == "/orders/export")
+            .unwrap();
+
+        let parameters = export_orders
+            .parameters
+            .iter()
+            .map(ParameterInfo::to_string)
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            parameters,
+            [
+                r#""page?": "integer""#,
+                r#""filter?" (application/json): {"since?":"string<date>","status":"open|closed"}"#,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn report_broken_operations() {
+        let humbler = Humbler::new(String::new(), "data/broken.json".to_owned());
+
+        assert!(matches!(
+            humbler.get_api_infos().await,
+            Err(Error::Reference { .. } | Error::UnsupportedSchema { .. })
+        ));
+
+        let config = Config {
+            lenient: true,
+            ..Default::default()
+        };
+        let (api_infos, warnings) = humbler.with_config(config).get_api_infos().await.unwrap();
+        let paths = api_infos
+            .iter()
+            .map(|api_info| api_info.path.as_str())
+            .collect::<Vec<&str>>();
+
+        assert_eq!(paths, ["/health"]);
+        assert_eq!(
+            warnings
+                .iter()
+                .map(Warning::to_string)
+                .collect::<Vec<String>>(),
+            [
+                "/paths/~1reports/get: could not resolve #/components/parameters/Missing: not found in components",
+                "/paths/~1reports/post: unsupported schema inline: array without items",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fall_back_on_missing_operation_id_and_tags() {
+        let humbler = Humbler::new(
+            "http://localhost".to_owned(),
+            "data/gateway.json".to_owned(),
+        );
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+        let status = api_infos
+            .iter()
+            .find(|api_info| api_info.path == "/status/{component}")
+            .unwrap();
+
+        assert_eq!(status.operation_id, "get_status__component_");
+        assert_eq!(status.tag, "untagged");
+        assert_eq!(
+            status.swagger_url,
+            "http://localhost#/default/get_status__component_"
+        );
+    }
+
+    #[test]
+    fn deep_link_per_style() {
+        let link = |link_style| {
+            deep_link(
+                link_style,
+                "http://localhost/docs",
+                Some("Pet Store"),
+                Some("getPetById"),
+                "get",
+                "/pet/{petId}",
+            )
+        };
+
+        assert_eq!(
+            link(LinkStyle::SwaggerUi),
+            "http://localhost/docs#/Pet%20Store/getPetById"
+        );
+        assert_eq!(
+            link(LinkStyle::Redoc),
+            "http://localhost/docs#operation/getPetById"
+        );
+        assert_eq!(
+            link(LinkStyle::Scalar),
+            "http://localhost/docs#tag/pet-store/GET/pet/{petId}"
+        );
+        assert_eq!(
+            link(LinkStyle::RapiDoc),
+            "http://localhost/docs#get-/pet/-petId-"
+        );
+
+        let without_operation_id = |link_style| {
+            deep_link(
+                link_style,
+                "http://localhost/docs",
+                Some("Pet Store"),
+                None,
+                "get",
+                "/pet/{petId}",
+            )
+        };
+
+        assert_eq!(
+            without_operation_id(LinkStyle::SwaggerUi),
+            "http://localhost/docs#/Pet%20Store/get_pet__petId_"
+        );
+        assert_eq!(
+            without_operation_id(LinkStyle::Redoc),
+            "http://localhost/docs#tag/Pet%20Store/paths/~1pet~1{petId}/get"
+        );
+    }
+
+    #[tokio::test]
+    async fn read_yaml_spec() {
+        let api_infos = |openapi_json_url: &str| {
+            let humbler = Humbler::new(String::new(), openapi_json_url.to_owned());
+
+            async move {
+                let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+
+                serde_json::to_value(api_infos).unwrap()
+            }
+        };
+
+        assert_eq!(
+            api_infos("data/gateway.yaml").await,
+            api_infos("data/gateway.json").await
+        );
+    }
+
+    #[tokio::test]
+    async fn upgrade_swagger2_spec() {
+        let humbler = Humbler::new(String::new(), "data/petstore-v2.json".to_owned());
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+        let find = |path: &str| {
+            api_infos
+                .iter()
+                .find(|api_info| api_info.path == path)
+                .unwrap()
+        };
+        let pet = r#"{"category?":{"id?":"integer<int64>","name?":"string"},"id?":"integer<int64>","name":"string","status?":"available|pending|sold","tag?":"string | null"}"#;
+
+        let add_pet = find("/pet");
+        assert_eq!(
+            add_pet.request_body.keys().collect::<Vec<_>>(),
+            ["application/json"]
+        );
+        assert_eq!(render_content(&add_pet.request_body), pet);
+        assert_eq!(
+            add_pet.responses["200"].content.keys().collect::<Vec<_>>(),
+            ["application/json", "application/xml"]
+        );
+        assert_eq!(
+            add_pet.render_responses(ResponseMode::All),
+            format!(
+                r#"200 (OK): {pet}<br>405 (Invalid input): {{"code?":"integer<int32>","message?":"string"}}"#
+            )
+        );
+
+        let find_pets_by_status = find("/pet/findByStatus")
+            .parameters
+            .iter()
+            .map(ParameterInfo::to_string)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            find_pets_by_status,
+            [
+                r#""status": ["available|pending|sold"]"#,
+                r#""limit?": "integer<int32>""#
+            ]
+        );
+
+        let upload_file = find("/pet/{petId}/uploadImage");
+        assert_eq!(
+            upload_file.parameters[0].to_string(),
+            r#""petId": "integer<int64>""#
+        );
+        assert_eq!(
+            upload_file.request_body["multipart/form-data"],
+            r#"{"additionalMetadata?":"string","file":"file"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn downgrade_openapi31_spec() {
+        let humbler = Humbler::new(String::new(), "data/pets-31.json".to_owned());
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+        let pet = r#"{"code?":{"oneOf":["string","integer"]},"id":"integer<int64>","kind?":"pet","name":"string | null","owner?":{"oneOf":[{"name?":"string"},"null"]},"tags?":[{"label?":"string"}]}"#;
+
+        let list_pets = &api_infos[0];
+        assert_eq!(list_pets.path, "/pets");
+        assert_eq!(
+            list_pets.parameters[0].to_string(),
+            r#""limit?": "integer | null""#
+        );
+        assert_eq!(
+            list_pets.render_responses(ResponseMode::Success),
+            format!("[{pet}]")
+        );
+
+        let new_pet = &api_infos[1];
+        assert_eq!(new_pet.path, "webhook newPet");
+        assert_eq!(render_content(&new_pet.request_body), pet);
+
+        let humbler = Humbler::new(String::new(), "data/webhooks-31.json".to_owned());
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+
+        let order_shipped = &api_infos[0];
+        assert_eq!(order_shipped.path, "webhook orderShipped");
+        assert_eq!(
+            render_content(&order_shipped.request_body),
+            r#"{"orderId":"string"}"#
+        );
+
+        let humbler = Humbler::new(String::new(), "data/defs-31.json".to_owned());
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+
+        assert_eq!(
+            api_infos[0].render_responses(ResponseMode::Success),
+            r#"{"item?":{"name?":"string"},"x?":"integer","y?":"integer"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn bundle_external_references() {
+        let humbler = Humbler::new(String::new(), "data/multi/openapi.yaml".to_owned());
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+        let user = r#"{"address?":{"city?":"string"},"id":"string"}"#;
+
+        let list_users = &api_infos[0];
+        assert_eq!(list_users.operation_id, "listUsers");
+        assert_eq!(
+            list_users.parameters[0].to_string(),
+            r#""page?": "integer""#
+        );
+        assert_eq!(
+            list_users.render_responses(ResponseMode::Success),
+            format!("[{user}]")
+        );
+
+        let get_user = &api_infos[1];
+        assert_eq!(
+            get_user.render_responses(ResponseMode::All),
+            format!(r#"200 (OK): {user}<br>404 (Not found): {{"message?":"string"}}"#)
+        );
+    }
+
+    #[tokio::test]
+    async fn bundle_remote_references() {
+        let server = MockServer::start().await;
+        let serve = |route: &str, body: &str| {
+            Mock::given(method("GET"))
+                .and(path(route.to_owned()))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body.to_owned()))
+        };
+
+        serve(
+            "/specs/openapi.json",
+            r#"{
+                "openapi": "3.0.3",
+                "info": { "title": "Remote", "version": "1.0.0" },
+                "paths": {
+                    "/users/{id}": {
+                        "get": {
+                            "operationId": "getUser",
+                            "responses": {
+                                "200": { "$ref": "common/responses.json#/User" }
+                            }
+                        }
+                    }
+                }
+            }"#,
+        )
+        .mount(&server)
+        .await;
+        serve(
+            "/specs/common/responses.json",
+            r#"{
+                "User": {
+                    "description": "OK",
+                    "content": {
+                        "application/json": { "schema": { "$ref": "../schemas/user.json" } }
+                    }
+                }
+            }"#,
+        )
+        .mount(&server)
+        .await;
+        serve(
+            "/specs/schemas/user.json",
+            r#"{ "type": "object", "properties": { "id": { "type": "string" } } }"#,
+        )
+        .mount(&server)
+        .await;
+
+        let humbler = Humbler::new(
+            String::new(),
+            format!("{}/specs/openapi.json", server.uri()),
+        );
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+
+        assert_eq!(
+            api_infos[0].render_responses(ResponseMode::Success),
+            r#"{"id?":"string"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn resolve_json_pointers() {
+        let humbler = Humbler::new(String::new(), "data/pointers.json".to_owned());
+        let (api_infos, _) = humbler.get_api_infos().await.unwrap();
+        let inner = r#"[{"name?":"string"}]"#;
+
+        let create_pet = &api_infos[1];
+        assert_eq!(create_pet.operation_id, "createPet");
+        assert_eq!(
+            create_pet.request_body["application/json"],
+            r#"{"dog?":{"bark?":"boolean"},"food?":"wet|dry","tilde?":"integer"}"#
+        );
+        assert_eq!(
+            create_pet.render_responses(ResponseMode::All),
+            format!("201 (OK): {inner}")
+        );
+
+        let list_pets = &api_infos[0];
+        assert_eq!(list_pets.render_responses(ResponseMode::Success), inner);
+    }
+
+    #[test]
+    fn escape_markdown_cells() {
+        let api_info = ApiInfo {
+            service: None,
+            path: "/pet/findByStatus".to_owned(),
+            method: "get".to_owned(),
+            operation_id: "findPetsByStatus".to_owned(),
+            tag: "pet".to_owned(),
+            parameters: vec![ParameterInfo {
+                name: "status".to_owned(),
+                location: ParameterLocation::Query,
+                required: true,
+                schema: json!("available|pending|sold"),
+                media_type: None,
+            }],
+            request_body: Content::from([(
+                "application/json".to_owned(),
+                r#"{"<string>":"integer<int32>"}"#.to_owned(),
+            )]),
+            responses: IndexMap::from([
+                (
+                    "200".to_owned(),
+                    ResponseInfo {
+                        description: "OK".to_owned(),
+                        content: Content::from([(
+                            "application/json".to_owned(),
+                            r#"[{"id":"integer<int64>"}]"#.to_owned(),
+                        )]),
+                    },
+                ),
+                (
+                    "400".to_owned(),
+                    ResponseInfo {
+                        description: "Invalid status,\r\nsee `status`\n".to_owned(),
+                        content: Content::new(),
+                    },
+                ),
+            ]),
+            swagger_url: "http://localhost#/pet/findPetsByStatus".to_owned(),
+        };
+        let markdown = render_markdown_table(&[api_info], ResponseMode::All);
+
+        assert_eq!(
+            markdown.lines().last(),
+            Some(concat!(
+                r#"| /pet/findByStatus | get | "status": "available\|pending\|sold" | "#,
+                r#"{"&lt;string&gt;":"integer&lt;int32&gt;"} | "#,
+                r#"200 (OK): [{"id":"integer&lt;int64&gt;"}]<br>400 (Invalid status,<br>see `status`) | "#,
+                "http://localhost#/pet/findPetsByStatus |"
+            ))
+        );
+    }
 }
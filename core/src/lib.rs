@@ -1,3 +1,12 @@
+pub mod bundle;
+pub mod catalogue;
 pub mod config;
+pub mod convert;
+pub mod discover;
+pub mod document;
+pub mod error;
+pub mod fetch;
 pub mod humbler;
 pub mod utils;
+
+pub use error::{Error, Result};
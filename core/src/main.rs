@@ -1,19 +1,60 @@
 use anyhow::Result;
 use dotenv::dotenv;
-use humbler_core::humbler::Humbler;
-use std::env;
+use humbler_core::{
+    catalogue::Catalogue,
+    config::{load_config, Config},
+    discover::{discover, SpecUrl},
+    humbler::Humbler,
+};
+use std::{env, path::Path};
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
     dotenv().ok();
+
+    let config = match Path::new(".humbler.toml").exists() {
+        true => load_config(".humbler.toml")?,
+        false => Config::default(),
+    };
+
+    if !config.services.is_empty() {
+        let catalogue = Catalogue::new(config).run().await?;
+
+        println!("{}", catalogue.render_markdown_table());
+
+        for warning in &catalogue.warnings {
+            eprintln!("warning: {warning}");
+        }
+
+        return Ok(());
+    }
+
     let swagger_ui_url = &env::var("SWAGGER_UI_URL").expect("SWAGGER_UI_URL must be set");
-    let openapi_json_url = &env::var("OPENAPI_JSON_URL").expect("OPENAPI_JSON_URL must be set");
 
-    let humbler = Humbler::new(swagger_ui_url.to_string(), openapi_json_url.to_string());
+    // Without OPENAPI_JSON_URL, every spec the Swagger UI lists is rendered, one table per
+    // springdoc group.
+    let specs = match env::var("OPENAPI_JSON_URL") {
+        Ok(url) => vec![SpecUrl { name: None, url }],
+        Err(_) => discover(&config.source.clone().with_env()?, swagger_ui_url).await?,
+    };
+
+    for spec in specs {
+        let humbler = Humbler::new(spec.ui_url(swagger_ui_url), spec.url.to_owned())
+            .with_config(config.clone())
+            .run()
+            .await?;
+        let markdown = humbler.render_markdown_table();
+
+        if let Some(name) = &spec.name {
+            println!("## {name}\n");
+        }
 
-    let markdown = humbler.run().await?.render_markdown_table();
+        println!("{}", markdown);
 
-    println!("{}", markdown);
+        for warning in &humbler.warnings {
+            eprintln!("warning: {warning}");
+        }
+    }
 
     Ok(())
 }
@@ -1,7 +1,15 @@
-use openapiv3::ReferenceOr;
+use crate::error::{Error, Result};
+use indexmap::IndexMap;
+use openapiv3::{Components, Parameter, ReferenceOr, RequestBody, Response, Schema};
+use percent_encoding::percent_decode_str;
 
 pub trait ReferenceOrExt<T> {
     fn into_reference(self) -> Option<String>;
+
+    /// Returns the item, following `$ref`s into `components` until one is found.
+    fn resolve(self, components: &Components) -> Result<T>
+    where
+        T: Component;
 }
 
 impl<T> ReferenceOrExt<T> for ReferenceOr<T> {
@@ -11,4 +19,100 @@ impl<T> ReferenceOrExt<T> for ReferenceOr<T> {
             ReferenceOr::Item(_) => None,
         }
     }
+
+    fn resolve(self, components: &Components) -> Result<T>
+    where
+        T: Component,
+    {
+        let mut visited = Vec::new();
+        let mut ref_or_item = self;
+
+        loop {
+            let reference = match ref_or_item {
+                ReferenceOr::Reference { reference } => reference,
+                ReferenceOr::Item(item) => return Ok(item),
+            };
+
+            if visited.contains(&reference) {
+                return Err(Error::reference(&reference, "circular reference"));
+            }
+
+            let name = component_name(&reference, T::SECTION)?;
+
+            ref_or_item = T::section(components)
+                .get(&name)
+                .ok_or_else(|| Error::reference(&reference, "not found in components"))?
+                .to_owned();
+            visited.push(reference);
+        }
+    }
+}
+
+/// Items that can be shared through a section of `components` and referenced as
+/// `#/components/<section>/<name>`.
+pub trait Component: Clone {
+    const SECTION: &'static str;
+
+    fn section(components: &Components) -> &IndexMap<String, ReferenceOr<Self>>;
+}
+
+impl Component for Schema {
+    const SECTION: &'static str = "schemas";
+
+    fn section(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.schemas
+    }
+}
+
+impl Component for Parameter {
+    const SECTION: &'static str = "parameters";
+
+    fn section(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.parameters
+    }
+}
+
+impl Component for RequestBody {
+    const SECTION: &'static str = "requestBodies";
+
+    fn section(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.request_bodies
+    }
+}
+
+impl Component for Response {
+    const SECTION: &'static str = "responses";
+
+    fn section(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.responses
+    }
+}
+
+/// Escapes a key for use as a JSON pointer token, e.g. `/pet/{petId}` → `~1pet~1{petId}`.
+pub fn pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Reverses [`pointer_token`]. `~1` is replaced first, so `~01` becomes `~1`.
+pub fn unescape_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Names the component a local `$ref` points at in `section`, decoding the pointer as
+/// RFC 6901 and RFC 3986 say, e.g. `#/components/schemas/Pet~1Dog` → `Pet/Dog`. Any
+/// other local reference was hoisted into `components` when the spec was bundled.
+pub fn component_name(reference: &str, section: &str) -> Result<String> {
+    let pointer = reference
+        .strip_prefix('#')
+        .ok_or_else(|| Error::reference(reference, "not a local reference"))?;
+    let pointer = percent_decode_str(pointer).decode_utf8_lossy();
+    let tokens = pointer.split('/').collect::<Vec<&str>>();
+
+    match tokens.as_slice() {
+        ["", "components", found, name] if *found == section => Ok(unescape_token(name)),
+        _ => Err(Error::reference(
+            reference,
+            format!("not in components/{section}"),
+        )),
+    }
 }
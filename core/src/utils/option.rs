@@ -1,11 +1,14 @@
-use anyhow::{anyhow, Error, Result};
+use crate::error::{Error, Result};
 
-pub trait OptionExt<T, E: ToString> {
-    fn to_result(self, error: E) -> Result<T, Error>;
+pub trait OptionExt<T> {
+    /// Turns `None` into [`Error::MissingField`] at `pointer`.
+    fn or_missing(self, pointer: impl ToString) -> Result<T>;
 }
 
-impl<T, E: ToString> OptionExt<T, E> for Option<T> {
-    fn to_result(self, error_msg: E) -> Result<T, Error> {
-        self.ok_or_else(|| anyhow!(error_msg.to_string()))
+impl<T> OptionExt<T> for Option<T> {
+    fn or_missing(self, pointer: impl ToString) -> Result<T> {
+        self.ok_or_else(|| Error::MissingField {
+            pointer: pointer.to_string(),
+        })
     }
 }
@@ -1,6 +1,6 @@
 use std::env;
 
-use humbler_core::humbler::{ApiInfo, Humbler};
+use humbler_core::humbler::ApiInfo;
 use leptos::{html::Input, prelude::*};
 use leptos_meta::{provide_meta_context, MetaTags, Stylesheet, Title};
 use leptos_router::{
@@ -52,7 +52,25 @@ pub fn App() -> impl IntoView {
 
 #[server]
 async fn search(keyword: String) -> Result<Vec<ApiInfo>, ServerFnError> {
+    use humbler_core::{catalogue::Catalogue, config::load_config, humbler::Humbler};
+    use std::path::Path;
+
     let current_dir = std::env::current_dir().expect("Failed to get current directory");
+
+    // Services listed in .humbler.toml are searched all at once.
+    if Path::new(".humbler.toml").exists() {
+        let config =
+            load_config(".humbler.toml").map_err(|e| ServerFnError::new(format!("Error: {e}")))?;
+
+        if !config.services.is_empty() {
+            return Catalogue::new(config)
+                .search(keyword)
+                .await
+                .map_err(|e| ServerFnError::new(format!("Error: {e}")))
+                .map(|catalogue| catalogue.api_infos);
+        }
+    }
+
     let swagger_ui_url = "http://localhost:4000/swagger-ui/index.html".to_owned();
     let openapi_json_url = "core/data/pet.json".to_owned();
 
@@ -70,7 +88,7 @@ static HEADERS: [&str; 6] = [
     "Method",
     "Parameters",
     "Request Body",
-    "Response",
+    "Responses",
     "Swagger URL",
 ];
 
@@ -97,20 +115,34 @@ fn HomePage() -> impl IntoView {
         </form>
             <div class="result">
                 <Suspense fallback=move || view!{ <p>"Loading..."</p> }>
-                    {move || value.get().map(|api_infos| view! {
+                    {move || value.get().map(|api_infos| {
+                        let api_infos = api_infos.unwrap_or_default();
+                        let services = api_infos.iter().any(|api_info| api_info.service.is_some());
+
+                        view! {
                         <table class="bg-red-300 border border-gray-400">
+                            {services.then(|| view!{ <th>"Service"</th> })}
                             {HEADERS.iter().map(|&header| view!{ <th>{header}</th> }).collect::<Vec<_>>()}
-                            {api_infos.unwrap_or_default().into_iter().map(|api_info| view! {
+                            {api_infos.into_iter().map(|api_info| view! {
                             <tr>
+                                    {services.then(|| view!{ <td>{api_info.service.clone()}</td> })}
                                     <td>{api_info.path}</td>
                                     <td>{api_info.method}</td>
-                                    <td>{api_info.parameters.iter().map(|(k, v)| format!("{}: {}", k, v)).collect::<Vec<_>>().join(", ")}</td>
-                                    <td>{api_info.request_body}</td>
-                                    <td>{api_info.response}</td>
+                                    <td>{api_info.parameters.iter().map(|parameter| parameter.to_string()).collect::<Vec<_>>().join(", ")}</td>
+                                    <td>{api_info.request_body.into_iter().map(|(media_type, body)| view! {
+                                        <p>{format!("{media_type}: {body}")}</p>
+                                    }).collect::<Vec<_>>()}</td>
+                                    <td>{api_info.responses.into_iter().map(|(status, response)| view! {
+                                        <p>{format!("{status} ({})", response.description)}</p>
+                                        {response.content.into_iter().map(|(media_type, body)| view! {
+                                            <p>{format!("{media_type}: {body}")}</p>
+                                        }).collect::<Vec<_>>()}
+                                    }).collect::<Vec<_>>()}</td>
                                     <td>{api_info.swagger_url}</td>
                             </tr>
                             }).collect::<Vec<_>>()}
                         </table>
+                        }
                     })}
                 </Suspense>
             </div>